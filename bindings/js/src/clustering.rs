@@ -1 +1,10 @@
+pub mod agglomerative;
+pub mod dbscan;
+pub mod gaussian_mixture;
+pub mod hdbscan;
 pub mod kmeans;
+pub mod kmedoids;
+pub mod mean_shift;
+pub mod mini_batch_kmeans;
+pub mod optics;
+pub mod spectral;
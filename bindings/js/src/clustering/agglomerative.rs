@@ -0,0 +1,103 @@
+//! Bindings for the agglomerative hierarchical clustering algorithm.
+
+use crate::clustering::kmeans::DistanceMetric;
+use crate::core::*;
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// How the distance between two clusters is computed from the distances between their points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "lowercase")]
+#[tsify(from_wasm_abi)]
+pub enum Linkage {
+    Single,
+    Complete,
+    Average,
+    #[default]
+    Ward,
+}
+
+impl From<Linkage> for toymlrs_clustering::agglomerative::Linkage {
+    fn from(linkage: Linkage) -> Self {
+        use toymlrs_clustering::agglomerative::Linkage as L;
+        match linkage {
+            Linkage::Single => L::Single,
+            Linkage::Complete => L::Complete,
+            Linkage::Average => L::Average,
+            Linkage::Ward => L::Ward,
+        }
+    }
+}
+
+/// The agglomerative clustering options, with exactly one of `nClusters` and
+/// `distanceThreshold`.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct AgglomerativeClusteringOptions {
+    /// Number of clusters to stop at
+    pub n_clusters: Option<usize>,
+    /// Linkage distance from which the clusters are no longer merged
+    pub distance_threshold: Option<f64>,
+    /// Defaults to `"ward"`
+    #[serde(default)]
+    pub linkage: Linkage,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+}
+
+/// Every merge as a SciPy linkage matrix, one `[left, right, distance, size]` row per merge.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct LinkageMatrix(pub Vec<[f64; 4]>);
+
+/// An agglomerative hierarchical clustering algorithm.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct AgglomerativeClustering {
+    inner: toymlrs_clustering::agglomerative::AgglomerativeClustering,
+}
+
+#[wasm_bindgen]
+impl AgglomerativeClustering {
+    /// Create a new AgglomerativeClustering instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: AgglomerativeClusteringOptions) -> Self {
+        let inner = toymlrs_clustering::agglomerative::AgglomerativeClustering::new(
+            opts.n_clusters,
+            opts.distance_threshold,
+            opts.linkage.into(),
+            opts.distance_metric.into(),
+        );
+        Self { inner }
+    }
+
+    /// Cluster the given data points.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    #[wasm_bindgen]
+    pub fn labels_(&self) -> Vec<usize> {
+        self.inner.get_labels().to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn n_clusters_(&self) -> usize {
+        self.inner.get_n_clusters()
+    }
+
+    #[wasm_bindgen]
+    pub fn linkage_matrix_(&self) -> LinkageMatrix {
+        LinkageMatrix(self.inner.get_linkage_matrix())
+    }
+}
@@ -0,0 +1,70 @@
+//! Bindings for the DBSCAN clustering algorithm.
+
+use crate::clustering::kmeans::DistanceMetric;
+use crate::core::*;
+use serde::Deserialize;
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// The DBSCAN options.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct DbscanOptions {
+    /// Largest distance between two neighbours, defaults to `0.5`
+    pub eps: Option<f64>,
+    /// Number of neighbours, the point itself included, making a core point, defaults to `5`
+    pub min_samples: Option<usize>,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+}
+
+/// A DBSCAN clustering algorithm.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct Dbscan {
+    inner: toymlrs_clustering::dbscan::Dbscan,
+}
+
+#[wasm_bindgen]
+impl Dbscan {
+    /// Create a new Dbscan instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: DbscanOptions) -> Self {
+        let inner = toymlrs_clustering::dbscan::Dbscan::new(
+            opts.eps.unwrap_or(0.5),
+            opts.min_samples.unwrap_or(5),
+            opts.distance_metric.into(),
+        );
+        Self { inner }
+    }
+
+    /// Cluster the given data points.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<i32>, JsError> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// The cluster of each point, `-1` for noise.
+    #[wasm_bindgen]
+    pub fn labels_(&self) -> Vec<i32> {
+        labels_with_noise(self.inner.get_labels())
+    }
+
+    #[wasm_bindgen]
+    pub fn core_sample_indices_(&self) -> Vec<usize> {
+        self.inner.get_core_sample_indices().to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn n_clusters_(&self) -> usize {
+        self.inner.get_n_clusters()
+    }
+}
@@ -0,0 +1,176 @@
+//! Bindings for the Gaussian mixture model.
+
+use crate::clustering::kmeans::CentroidsInitMethod;
+use crate::core::*;
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// The shape of the covariance matrices of the components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "lowercase")]
+#[tsify(from_wasm_abi)]
+pub enum CovarianceType {
+    #[default]
+    Full,
+    Diag,
+    Spherical,
+    Tied,
+}
+
+impl From<CovarianceType> for toymlrs_clustering::gaussian_mixture::CovarianceType {
+    fn from(covariance_type: CovarianceType) -> Self {
+        use toymlrs_clustering::gaussian_mixture::CovarianceType as Type;
+        match covariance_type {
+            CovarianceType::Full => Type::Full,
+            CovarianceType::Diag => Type::Diag,
+            CovarianceType::Spherical => Type::Spherical,
+            CovarianceType::Tied => Type::Tied,
+        }
+    }
+}
+
+/// The Gaussian mixture options.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct GaussianMixtureOptions {
+    /// Number of components, defaults to `1`
+    pub n_components: Option<usize>,
+    /// Defaults to `"full"`
+    #[serde(default)]
+    pub covariance_type: CovarianceType,
+    /// Convergence tolerance on the mean log-likelihood, defaults to `1e-3`
+    pub tol: Option<f64>,
+    /// Added to the diagonal of the covariances, defaults to `1e-6`
+    pub reg_covar: Option<f64>,
+    /// Defaults to `100`
+    pub max_iter: Option<usize>,
+    /// Number of runs with different seeds, defaults to `1`
+    pub n_init: Option<usize>,
+    /// How the means are seeded, defaults to `"kmeans++"`
+    #[serde(default)]
+    pub init_method: CentroidsInitMethod,
+    pub random_seed: Option<u64>,
+}
+
+/// One row per point, one column per component.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Probabilities(pub Vec<Vec<f64>>);
+
+/// One array per component, flattened row-major for the full and tied covariance types.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Matrices(pub Vec<Vec<f64>>);
+
+/// A Gaussian mixture model.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct GaussianMixture {
+    inner: toymlrs_clustering::gaussian_mixture::GaussianMixture,
+}
+
+#[wasm_bindgen]
+impl GaussianMixture {
+    /// Create a new GaussianMixture instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: GaussianMixtureOptions) -> Self {
+        let mut inner = toymlrs_clustering::gaussian_mixture::GaussianMixture::new(
+            opts.n_components.unwrap_or(1),
+            opts.covariance_type.into(),
+            opts.max_iter.unwrap_or(100),
+            opts.init_method.into(),
+            opts.random_seed,
+        );
+        if let Some(tol) = opts.tol {
+            inner.tol = tol;
+        }
+        if let Some(reg_covar) = opts.reg_covar {
+            inner.reg_covar = reg_covar;
+        }
+        if let Some(n_init) = opts.n_init {
+            inner.n_init = n_init;
+        }
+        Self { inner }
+    }
+
+    /// Fit the model to the given data points.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        Ok(self.inner.fit_predict(point_values.convert()?)?.to_vec())
+    }
+
+    /// The most probable component of each point.
+    #[wasm_bindgen]
+    pub fn predict(&self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        Ok(self.inner.predict(point_values.convert()?)?)
+    }
+
+    /// Probability of each component for each point.
+    #[wasm_bindgen]
+    pub fn predict_proba(&self, point_values: VecVecF64) -> Result<Probabilities, JsError> {
+        Ok(Probabilities(
+            self.inner.predict_proba(point_values.convert()?)?,
+        ))
+    }
+
+    /// Log-likelihood of each point under the model.
+    #[wasm_bindgen]
+    pub fn score_samples(&self, point_values: VecVecF64) -> Result<Vec<f64>, JsError> {
+        Ok(self.inner.score_samples(point_values.convert()?)?)
+    }
+
+    /// Mean log-likelihood of the points under the model.
+    #[wasm_bindgen]
+    pub fn score(&self, point_values: VecVecF64) -> Result<f64, JsError> {
+        Ok(self.inner.score(point_values.convert()?)?)
+    }
+
+    /// Bayesian information criterion on the points, the lower the better.
+    #[wasm_bindgen]
+    pub fn bic(&self, point_values: VecVecF64) -> Result<f64, JsError> {
+        Ok(self.inner.bic(point_values.convert()?)?)
+    }
+
+    /// Akaike information criterion on the points, the lower the better.
+    #[wasm_bindgen]
+    pub fn aic(&self, point_values: VecVecF64) -> Result<f64, JsError> {
+        Ok(self.inner.aic(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn weights_(&self) -> Vec<f64> {
+        self.inner.get_weights().to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn means_(&self) -> Matrices {
+        Matrices(self.inner.get_means().to_vec())
+    }
+
+    #[wasm_bindgen]
+    pub fn covariances_(&self) -> Matrices {
+        Matrices(self.inner.get_covariances().to_vec())
+    }
+
+    #[wasm_bindgen]
+    pub fn converged_(&self) -> bool {
+        self.inner.get_converged()
+    }
+
+    #[wasm_bindgen]
+    pub fn n_iter_(&self) -> usize {
+        self.inner.get_n_iter()
+    }
+
+    #[wasm_bindgen]
+    pub fn lower_bound_(&self) -> f64 {
+        self.inner.get_lower_bound()
+    }
+}
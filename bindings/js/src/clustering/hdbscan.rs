@@ -0,0 +1,133 @@
+//! Bindings for the HDBSCAN clustering algorithm.
+
+use crate::clustering::kmeans::DistanceMetric;
+use crate::core::*;
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// How the flat clustering is read from the condensed tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "lowercase")]
+#[tsify(from_wasm_abi)]
+pub enum ClusterSelectionMethod {
+    #[default]
+    Eom,
+    Leaf,
+}
+
+impl From<ClusterSelectionMethod> for toymlrs_clustering::hdbscan::ClusterSelectionMethod {
+    fn from(method: ClusterSelectionMethod) -> Self {
+        use toymlrs_clustering::hdbscan::ClusterSelectionMethod as Method;
+        match method {
+            ClusterSelectionMethod::Eom => Method::Eom,
+            ClusterSelectionMethod::Leaf => Method::Leaf,
+        }
+    }
+}
+
+/// The HDBSCAN options.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct HdbscanOptions {
+    /// Smallest number of points making a cluster, defaults to `5`
+    pub min_cluster_size: Option<usize>,
+    /// Number of neighbours, the point itself included, for the core distance, defaults to
+    /// `minClusterSize`
+    pub min_samples: Option<usize>,
+    /// Defaults to `"eom"`, `"leaf"` gives smaller and more homogeneous clusters
+    #[serde(default)]
+    pub cluster_selection_method: ClusterSelectionMethod,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+}
+
+/// An edge of the condensed tree, from a cluster to a child cluster or to a point leaving it.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct CondensedTreeEdge {
+    pub parent: usize,
+    pub child: usize,
+    pub lambda: f64,
+    pub child_size: usize,
+}
+
+/// The condensed tree, parents before their children.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct CondensedTree(pub Vec<CondensedTreeEdge>);
+
+/// A HDBSCAN clustering algorithm.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct Hdbscan {
+    inner: toymlrs_clustering::hdbscan::Hdbscan,
+}
+
+#[wasm_bindgen]
+impl Hdbscan {
+    /// Create a new Hdbscan instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: HdbscanOptions) -> Self {
+        let inner = toymlrs_clustering::hdbscan::Hdbscan::new(
+            opts.min_cluster_size.unwrap_or(5),
+            opts.min_samples,
+            opts.cluster_selection_method.into(),
+            opts.distance_metric.into(),
+        );
+        Self { inner }
+    }
+
+    /// Cluster the given data points.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<i32>, JsError> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// The cluster of each point, `-1` for noise.
+    #[wasm_bindgen]
+    pub fn labels_(&self) -> Vec<i32> {
+        labels_with_noise(self.inner.get_labels())
+    }
+
+    /// How strongly each point belongs to its cluster, `0` for noise.
+    #[wasm_bindgen]
+    pub fn probabilities_(&self) -> Vec<f64> {
+        self.inner.get_probabilities().to_vec()
+    }
+
+    /// GLOSH outlier score of each point, the higher the more of an outlier.
+    #[wasm_bindgen]
+    pub fn outlier_scores_(&self) -> Vec<f64> {
+        self.inner.get_outlier_scores().to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn condensed_tree_(&self) -> CondensedTree {
+        CondensedTree(
+            self.inner
+                .get_condensed_tree()
+                .iter()
+                .map(|edge| CondensedTreeEdge {
+                    parent: edge.parent,
+                    child: edge.child,
+                    lambda: edge.lambda,
+                    child_size: edge.child_size,
+                })
+                .collect(),
+        )
+    }
+
+    #[wasm_bindgen]
+    pub fn n_clusters_(&self) -> usize {
+        self.inner.get_n_clusters()
+    }
+}
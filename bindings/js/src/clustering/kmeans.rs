@@ -15,6 +15,10 @@ pub enum CentroidsInitMethod {
     #[default]
     #[serde(rename = "kmeans++")]
     KmeansPlusPlus,
+    #[serde(rename = "greedy-kmeans++")]
+    GreedyKmeansPlusPlus,
+    #[serde(rename = "kmeans||")]
+    KmeansParallel,
 }
 
 impl From<CentroidsInitMethod> for toymlrs_clustering::kmeans::CentroidsInitMethod {
@@ -24,6 +28,85 @@ impl From<CentroidsInitMethod> for toymlrs_clustering::kmeans::CentroidsInitMeth
             CentroidsInitMethod::KmeansPlusPlus => {
                 toymlrs_clustering::kmeans::CentroidsInitMethod::KmeansPlusPlus
             }
+            CentroidsInitMethod::GreedyKmeansPlusPlus => {
+                toymlrs_clustering::kmeans::CentroidsInitMethod::GreedyKmeansPlusPlus
+            }
+            CentroidsInitMethod::KmeansParallel => {
+                toymlrs_clustering::kmeans::CentroidsInitMethod::KmeansParallel
+            }
+        }
+    }
+}
+
+/// The distance used to assign points to centroids.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "lowercase")]
+#[tsify(from_wasm_abi)]
+pub enum DistanceMetric {
+    #[default]
+    Euclidean,
+    #[serde(rename = "sqeuclidean")]
+    SquaredEuclidean,
+    Manhattan,
+    Chebyshev,
+    Minkowski(f64),
+    Cosine,
+}
+
+impl From<DistanceMetric> for toymlrs_clustering::kmeans::DistanceMetric {
+    fn from(metric: DistanceMetric) -> Self {
+        use toymlrs_clustering::kmeans::DistanceMetric as Metric;
+        match metric {
+            DistanceMetric::Euclidean => Metric::Euclidean,
+            DistanceMetric::SquaredEuclidean => Metric::SquaredEuclidean,
+            DistanceMetric::Manhattan => Metric::Manhattan,
+            DistanceMetric::Chebyshev => Metric::Chebyshev,
+            DistanceMetric::Minkowski(p) => Metric::Minkowski(p),
+            DistanceMetric::Cosine => Metric::Cosine,
+        }
+    }
+}
+
+/// How to handle a centroid that attracts no point during the iterations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "kebab-case")]
+#[tsify(from_wasm_abi)]
+pub enum EmptyClusterStrategy {
+    Error,
+    #[default]
+    FarthestPoint,
+    HighestSse,
+}
+
+impl From<EmptyClusterStrategy> for toymlrs_clustering::kmeans::EmptyClusterStrategy {
+    fn from(strategy: EmptyClusterStrategy) -> Self {
+        use toymlrs_clustering::kmeans::EmptyClusterStrategy as Strategy;
+        match strategy {
+            EmptyClusterStrategy::Error => Strategy::Error,
+            EmptyClusterStrategy::FarthestPoint => Strategy::FarthestPoint,
+            EmptyClusterStrategy::HighestSse => Strategy::HighestSse,
+        }
+    }
+}
+
+/// How the points are assigned to their nearest centroid at each iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "lowercase")]
+#[tsify(from_wasm_abi)]
+pub enum KmeansAlgorithm {
+    #[default]
+    Lloyd,
+    Elkan,
+    Hamerly,
+}
+
+impl From<KmeansAlgorithm> for toymlrs_clustering::kmeans::KmeansAlgorithm {
+    fn from(algorithm: KmeansAlgorithm) -> Self {
+        use toymlrs_clustering::kmeans::KmeansAlgorithm as Algorithm;
+        match algorithm {
+            KmeansAlgorithm::Lloyd => Algorithm::Lloyd,
+            KmeansAlgorithm::Elkan => Algorithm::Elkan,
+            KmeansAlgorithm::Hamerly => Algorithm::Hamerly,
         }
     }
 }
@@ -36,8 +119,23 @@ pub struct KmeansOptions {
     /// The k in kmeans
     pub k: usize,
     pub centroids_init_method: CentroidsInitMethod,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
     pub max_iter: usize,
     pub random_seed: Option<u64>,
+    /// Convergence tolerance on the centroid shift, defaults to `1e-4`
+    pub tol: Option<f64>,
+    /// Number of runs with different centroid seeds, defaults to `1`
+    pub n_init: Option<usize>,
+    /// Defaults to `"farthest-point"`
+    #[serde(default)]
+    pub empty_cluster_strategy: EmptyClusterStrategy,
+    /// Defaults to `"lloyd"`, `"elkan"` and `"hamerly"` skip distance computations
+    #[serde(default)]
+    pub algorithm: KmeansAlgorithm,
+    /// Centroids to start from, one array per centroid, e.g. the centroids of a previous fit
+    pub init_centroids: Option<Vec<Vec<f64>>>,
 }
 
 /// A Kmeans clustering algorithm.
@@ -83,39 +181,104 @@ impl From<&toymlrs_clustering::kmeans::Clusters> for Clusters {
     }
 }
 
+/// Distances from each point (row) to every centroid (column).
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Distances(pub Vec<Vec<f64>>);
+
 #[wasm_bindgen]
 impl Kmeans {
     /// Create a new Kmeans instance.
     #[wasm_bindgen(constructor)]
-    pub fn new(opts: KmeansOptions) -> Self {
-        Self {
-            inner: toymlrs_clustering::kmeans::Kmeans::new(
-                opts.k,
-                opts.max_iter,
-                opts.centroids_init_method.into(),
-                toymlrs_clustering::kmeans::DistanceMetric::Euclidean,
-                opts.random_seed,
-            ),
+    pub fn new(opts: KmeansOptions) -> Result<Kmeans, JsError> {
+        let mut inner = toymlrs_clustering::kmeans::Kmeans::new(
+            opts.k,
+            opts.max_iter,
+            opts.centroids_init_method.into(),
+            opts.distance_metric.into(),
+            opts.random_seed,
+        );
+        if let Some(tol) = opts.tol {
+            inner.tol = tol;
+        }
+        if let Some(n_init) = opts.n_init {
+            inner.n_init = n_init;
         }
+        inner.empty_cluster_strategy = opts.empty_cluster_strategy.into();
+        inner.algorithm = opts.algorithm.into();
+        if let Some(init_centroids) = opts.init_centroids {
+            inner = inner.with_init_centroids(init_centroids)?;
+        }
+        Ok(Self { inner })
     }
 
-    /// Fit the Kmeans clustering algorithm to the given data points.
+    /// Fit the Kmeans clustering algorithm to the given data points, optionally weighted.
     #[wasm_bindgen]
-    pub fn fit(&mut self, point_values: VecVecF64) {
-        self.inner.fit(point_values.convert().unwrap())
+    pub fn fit(
+        &mut self,
+        point_values: VecVecF64,
+        sample_weight: Option<VecF64>,
+    ) -> Result<(), JsError> {
+        let points = point_values.convert()?;
+        match sample_weight {
+            Some(sample_weight) => {
+                Ok(self.inner.fit_weighted(points, &sample_weight.convert()?)?)
+            }
+            None => Ok(self.inner.fit(points)?),
+        }
     }
 
     #[wasm_bindgen]
-    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
-        self.fit(point_values);
+    pub fn fit_predict(
+        &mut self,
+        point_values: VecVecF64,
+        sample_weight: Option<VecF64>,
+    ) -> Result<Vec<usize>, JsError> {
+        self.fit(point_values, sample_weight)?;
         self.labels_()
     }
 
+    /// Assign each point to the nearest centroid of the fitted model.
+    #[wasm_bindgen]
+    pub fn predict(&self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        Ok(self.inner.predict(point_values.convert()?)?.0)
+    }
+
+    /// Distances from each point to every centroid of the fitted model.
+    #[wasm_bindgen]
+    pub fn transform(&self, point_values: VecVecF64) -> Result<Distances, JsError> {
+        Ok(Distances(self.inner.transform(point_values.convert()?)?))
+    }
+
     #[wasm_bindgen]
     pub fn labels_(&self) -> Result<Vec<usize>, JsError> {
         Ok(self.inner.get_labels().0.to_vec())
     }
 
+    /// Within-cluster sum of squared distances of the fitted model.
+    #[wasm_bindgen]
+    pub fn inertia_(&self) -> f64 {
+        self.inner.get_inertia()
+    }
+
+    /// Number of iterations run by the last fit.
+    #[wasm_bindgen]
+    pub fn n_iter_(&self) -> usize {
+        self.inner.get_n_iter()
+    }
+
+    /// Index of the run kept among the `nInit` runs of the last fit.
+    #[wasm_bindgen]
+    pub fn best_init_(&self) -> usize {
+        self.inner.get_best_init()
+    }
+
+    /// Number of distances computed to assign the points during the last fit.
+    #[wasm_bindgen]
+    pub fn n_distances_(&self) -> usize {
+        self.inner.get_n_distances()
+    }
+
     #[wasm_bindgen]
     pub fn centroids_(&self) -> Result<Centroids, JsError> {
         Ok(self.inner.get_centroids().into())
@@ -125,4 +288,25 @@ impl Kmeans {
     pub fn cluster_(&self) -> Result<Clusters, JsError> {
         Ok(self.inner.get_clusters().into())
     }
+
+    /// The model, hyperparameters and fitted state, as a plain object for `JSON.stringify`.
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> Result<JsValue, JsError> {
+        js_sys::JSON::parse(&self.inner.to_json()?)
+            .map_err(|_| JsError::new("could not save the model as JSON"))
+    }
+
+    /// Load a model saved with `toJSON`, from the object or its JSON string.
+    #[wasm_bindgen(js_name = fromJSON)]
+    pub fn from_json(value: JsValue) -> Result<Kmeans, JsError> {
+        let json = match value.as_string() {
+            Some(json) => json,
+            None => js_sys::JSON::stringify(&value)
+                .map_err(|_| JsError::new("TypeError: expected a saved model or its JSON string"))?
+                .into(),
+        };
+        Ok(Kmeans {
+            inner: toymlrs_clustering::kmeans::Kmeans::from_json(&json)?,
+        })
+    }
 }
@@ -0,0 +1,113 @@
+//! Bindings for the k-medoids clustering algorithm.
+
+use crate::clustering::kmeans::DistanceMetric;
+use crate::core::*;
+use serde::Deserialize;
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// How the medoids are searched for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "lowercase")]
+#[tsify(from_wasm_abi)]
+pub enum KmedoidsAlgorithm {
+    Pam,
+    #[default]
+    FasterPam,
+}
+
+impl From<KmedoidsAlgorithm> for toymlrs_clustering::kmedoids::KmedoidsAlgorithm {
+    fn from(algorithm: KmedoidsAlgorithm) -> Self {
+        use toymlrs_clustering::kmedoids::KmedoidsAlgorithm as Algorithm;
+        match algorithm {
+            KmedoidsAlgorithm::Pam => Algorithm::Pam,
+            KmedoidsAlgorithm::FasterPam => Algorithm::FasterPam,
+        }
+    }
+}
+
+/// The k-medoids options.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct KmedoidsOptions {
+    pub k: usize,
+    /// Largest number of swaps for PAM, of passes over the points for FasterPAM, defaults to `100`
+    pub max_iter: Option<usize>,
+    /// Defaults to `"fasterpam"`
+    #[serde(default)]
+    pub algorithm: KmedoidsAlgorithm,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+    pub random_seed: Option<u64>,
+}
+
+/// A k-medoids clustering algorithm.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct Kmedoids {
+    inner: toymlrs_clustering::kmedoids::Kmedoids,
+}
+
+#[wasm_bindgen]
+impl Kmedoids {
+    /// Create a new Kmedoids instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: KmedoidsOptions) -> Self {
+        let inner = toymlrs_clustering::kmedoids::Kmedoids::new(
+            opts.k,
+            opts.max_iter.unwrap_or(100),
+            opts.algorithm.into(),
+            opts.distance_metric.into(),
+            opts.random_seed,
+        );
+        Self { inner }
+    }
+
+    /// Cluster the given data points.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    /// Cluster from the square matrix of the distances between the points.
+    #[wasm_bindgen]
+    pub fn fit_precomputed(&mut self, distances: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit_precomputed(distances.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// Assign each point to its nearest medoid, for a model fitted with `fit`.
+    #[wasm_bindgen]
+    pub fn predict(&self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        Ok(self.inner.predict(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn labels_(&self) -> Vec<usize> {
+        self.inner.get_labels().to_vec()
+    }
+
+    /// Index in the dataset of the medoid of each cluster.
+    #[wasm_bindgen]
+    pub fn medoid_indices_(&self) -> Vec<usize> {
+        self.inner.get_medoid_indices().to_vec()
+    }
+
+    /// Sum of the distances from each point to its medoid.
+    #[wasm_bindgen]
+    pub fn inertia_(&self) -> f64 {
+        self.inner.get_inertia()
+    }
+
+    #[wasm_bindgen]
+    pub fn n_iter_(&self) -> usize {
+        self.inner.get_n_iter()
+    }
+}
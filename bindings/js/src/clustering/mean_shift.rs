@@ -0,0 +1,131 @@
+//! Bindings for the mean shift clustering algorithm.
+
+use crate::clustering::kmeans::{Centroids, DistanceMetric};
+use crate::core::*;
+use serde::Deserialize;
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// How the points around a center weigh in its next position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "lowercase")]
+#[tsify(from_wasm_abi)]
+pub enum Kernel {
+    #[default]
+    Flat,
+    Gaussian,
+}
+
+impl From<Kernel> for toymlrs_clustering::mean_shift::Kernel {
+    fn from(kernel: Kernel) -> Self {
+        match kernel {
+            Kernel::Flat => toymlrs_clustering::mean_shift::Kernel::Flat,
+            Kernel::Gaussian => toymlrs_clustering::mean_shift::Kernel::Gaussian,
+        }
+    }
+}
+
+/// The mean shift options.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct MeanShiftOptions {
+    /// Radius of the kernel, estimated with `estimate_bandwidth` and a quantile of `0.3` if
+    /// missing
+    pub bandwidth: Option<f64>,
+    /// Defaults to `"flat"`
+    #[serde(default)]
+    pub kernel: Kernel,
+    /// Seed from a grid of bandwidth-sized bins instead of every point, defaults to `false`
+    pub bin_seeding: Option<bool>,
+    /// Fewest points in a bin to seed from it, defaults to `1`
+    pub min_bin_freq: Option<usize>,
+    /// Defaults to `300`
+    pub max_iter: Option<usize>,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+}
+
+/// A mean shift clustering algorithm.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct MeanShift {
+    inner: toymlrs_clustering::mean_shift::MeanShift,
+}
+
+#[wasm_bindgen]
+impl MeanShift {
+    /// Create a new MeanShift instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: MeanShiftOptions) -> Self {
+        let mut inner = toymlrs_clustering::mean_shift::MeanShift::new(
+            opts.bandwidth,
+            opts.kernel.into(),
+            opts.bin_seeding.unwrap_or(false),
+            opts.distance_metric.into(),
+        );
+        if let Some(min_bin_freq) = opts.min_bin_freq {
+            inner.min_bin_freq = min_bin_freq;
+        }
+        if let Some(max_iter) = opts.max_iter {
+            inner.max_iter = max_iter;
+        }
+        Self { inner }
+    }
+
+    /// Cluster the given data points.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// Assign each point to its nearest cluster center.
+    #[wasm_bindgen]
+    pub fn predict(&self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        Ok(self.inner.predict(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn labels_(&self) -> Vec<usize> {
+        self.inner.get_labels().to_vec()
+    }
+
+    /// The modes of the density, keyed by label like the k-means centroids.
+    #[wasm_bindgen]
+    pub fn cluster_centers_(&self) -> Centroids {
+        self.inner.get_cluster_centers().into()
+    }
+
+    /// The bandwidth used by the last fit, given or estimated.
+    #[wasm_bindgen]
+    pub fn bandwidth_(&self) -> f64 {
+        self.inner.get_bandwidth()
+    }
+
+    #[wasm_bindgen]
+    pub fn n_iter_(&self) -> usize {
+        self.inner.get_n_iter()
+    }
+}
+
+/// Estimate a bandwidth for `MeanShift`, the mean distance from each point to its
+/// `quantile * n_samples`-th nearest point, the quantile defaulting to `0.3`.
+#[wasm_bindgen]
+pub fn estimate_bandwidth(
+    point_values: VecVecF64,
+    quantile: Option<f64>,
+    distance_metric: Option<DistanceMetric>,
+) -> Result<f64, JsError> {
+    Ok(toymlrs_clustering::mean_shift::estimate_bandwidth(
+        point_values.convert()?,
+        quantile.unwrap_or(0.3),
+        &toymlrs_clustering::distance::DistanceMetric::from(distance_metric.unwrap_or_default()),
+    )?)
+}
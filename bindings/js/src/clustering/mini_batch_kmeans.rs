@@ -0,0 +1,113 @@
+//! Bindings for the mini-batch kmeans clustering algorithm.
+
+use crate::clustering::kmeans::{Centroids, CentroidsInitMethod, DistanceMetric, Distances};
+use crate::core::*;
+use serde::Deserialize;
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// The mini-batch kmeans options.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct MiniBatchKmeansOptions {
+    /// The k in kmeans
+    pub k: usize,
+    /// Number of points in each batch, defaults to `1024`
+    pub batch_size: Option<usize>,
+    /// Maximum number of passes over the dataset, defaults to `100`
+    pub max_iter: Option<usize>,
+    #[serde(default)]
+    pub centroids_init_method: CentroidsInitMethod,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+    pub random_seed: Option<u64>,
+    /// Stop once no centroid moves more than `tol` during a batch, defaults to `0`
+    pub tol: Option<f64>,
+}
+
+/// A mini-batch Kmeans clustering algorithm.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct MiniBatchKmeans {
+    inner: toymlrs_clustering::mini_batch_kmeans::MiniBatchKmeans,
+}
+
+#[wasm_bindgen]
+impl MiniBatchKmeans {
+    /// Create a new MiniBatchKmeans instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: MiniBatchKmeansOptions) -> Self {
+        let mut inner = toymlrs_clustering::mini_batch_kmeans::MiniBatchKmeans::new(
+            opts.k,
+            opts.batch_size.unwrap_or(1024),
+            opts.max_iter.unwrap_or(100),
+            opts.centroids_init_method.into(),
+            opts.distance_metric.into(),
+            opts.random_seed,
+        );
+        if let Some(tol) = opts.tol {
+            inner.tol = tol;
+        }
+        Self { inner }
+    }
+
+    /// Fit the centroids with batches sampled from the given data points.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    /// Update the centroids with one more chunk of data points.
+    #[wasm_bindgen]
+    pub fn partial_fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.partial_fit(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        self.fit(point_values)?;
+        self.labels_()
+    }
+
+    /// Assign each point to the nearest centroid of the fitted model.
+    #[wasm_bindgen]
+    pub fn predict(&self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        Ok(self.inner.predict(point_values.convert()?)?.0)
+    }
+
+    /// Distances from each point to every centroid of the fitted model.
+    #[wasm_bindgen]
+    pub fn transform(&self, point_values: VecVecF64) -> Result<Distances, JsError> {
+        Ok(Distances(self.inner.transform(point_values.convert()?)?))
+    }
+
+    #[wasm_bindgen]
+    pub fn labels_(&self) -> Result<Vec<usize>, JsError> {
+        Ok(self.inner.get_labels().0.to_vec())
+    }
+
+    /// Within-cluster sum of squared distances of the points of the last fit.
+    #[wasm_bindgen]
+    pub fn inertia_(&self) -> f64 {
+        self.inner.get_inertia()
+    }
+
+    /// Number of batches used to move the centroids.
+    #[wasm_bindgen]
+    pub fn n_steps_(&self) -> usize {
+        self.inner.get_n_steps()
+    }
+
+    /// Number of points assigned to each centroid so far.
+    #[wasm_bindgen]
+    pub fn counts_(&self) -> Vec<usize> {
+        self.inner.get_counts().to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn centroids_(&self) -> Result<Centroids, JsError> {
+        Ok(self.inner.get_centroids().into())
+    }
+}
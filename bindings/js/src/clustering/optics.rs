@@ -0,0 +1,167 @@
+//! Bindings for the OPTICS clustering algorithm.
+
+use crate::clustering::kmeans::DistanceMetric;
+use crate::core::*;
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// How the clusters are read from the reachability plot when fitting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "lowercase")]
+#[tsify(from_wasm_abi)]
+pub enum ClusterMethod {
+    #[default]
+    Xi,
+    Dbscan,
+}
+
+impl From<ClusterMethod> for toymlrs_clustering::optics::ClusterMethod {
+    fn from(method: ClusterMethod) -> Self {
+        match method {
+            ClusterMethod::Xi => toymlrs_clustering::optics::ClusterMethod::Xi,
+            ClusterMethod::Dbscan => toymlrs_clustering::optics::ClusterMethod::Dbscan,
+        }
+    }
+}
+
+/// The OPTICS options.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct OpticsOptions {
+    /// Number of neighbours, the point itself included, making a core point, defaults to `5`
+    pub min_samples: Option<usize>,
+    /// Largest distance between two neighbours, defaults to `Infinity`
+    pub max_eps: Option<f64>,
+    /// Defaults to `"xi"`
+    #[serde(default)]
+    pub cluster_method: ClusterMethod,
+    /// Reachability threshold of the `"dbscan"` method, defaults to `maxEps`
+    pub eps: Option<f64>,
+    /// Smallest relative change of the reachability making a steep area for the `"xi"` method,
+    /// defaults to `0.05`
+    pub xi: Option<f64>,
+    /// Fewest points in a cluster of the `"xi"` method, defaults to `minSamples`
+    pub min_cluster_size: Option<usize>,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+}
+
+/// The clusters found by the Xi method.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(into_wasm_abi)]
+pub struct XiClusters {
+    /// The cluster of each point, `-1` for noise
+    pub labels: Vec<i32>,
+    /// Every cluster found, as an inclusive `[start, end]` range of positions in the ordering
+    pub hierarchy: Vec<(usize, usize)>,
+}
+
+/// Inclusive `[start, end]` ranges of positions in the ordering.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct ClusterHierarchy(pub Vec<(usize, usize)>);
+
+/// An OPTICS clustering algorithm.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct Optics {
+    inner: toymlrs_clustering::optics::Optics,
+}
+
+#[wasm_bindgen]
+impl Optics {
+    /// Create a new Optics instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: OpticsOptions) -> Self {
+        let mut inner = toymlrs_clustering::optics::Optics::new(
+            opts.min_samples.unwrap_or(5),
+            opts.max_eps.unwrap_or(f64::INFINITY),
+            opts.cluster_method.into(),
+            opts.distance_metric.into(),
+        );
+        inner.eps = opts.eps;
+        if let Some(xi) = opts.xi {
+            inner.xi = xi;
+        }
+        inner.min_cluster_size = opts.min_cluster_size;
+        Self { inner }
+    }
+
+    /// Order the given data points and cluster them.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<i32>, JsError> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// The clusters DBSCAN would find with `eps`, `-1` for noise, without refitting.
+    #[wasm_bindgen]
+    pub fn extract_dbscan(&self, eps: f64) -> Result<Vec<i32>, JsError> {
+        Ok(labels_with_noise(&self.inner.extract_dbscan(eps)?))
+    }
+
+    /// The clusters found by the Xi method, without refitting.
+    #[wasm_bindgen]
+    pub fn extract_xi(
+        &self,
+        xi: f64,
+        min_cluster_size: Option<usize>,
+    ) -> Result<XiClusters, JsError> {
+        let clusters = self
+            .inner
+            .extract_xi(xi, min_cluster_size.unwrap_or(self.inner.min_samples))?;
+        Ok(XiClusters {
+            labels: labels_with_noise(&clusters.labels),
+            hierarchy: clusters.hierarchy,
+        })
+    }
+
+    /// The cluster of each point, `-1` for noise.
+    #[wasm_bindgen]
+    pub fn labels_(&self) -> Vec<i32> {
+        labels_with_noise(self.inner.get_labels())
+    }
+
+    /// The points in the order they were visited.
+    #[wasm_bindgen]
+    pub fn ordering_(&self) -> Vec<usize> {
+        self.inner.get_ordering().to_vec()
+    }
+
+    /// The reachability distance of each point, indexed by point.
+    #[wasm_bindgen]
+    pub fn reachability_(&self) -> Vec<f64> {
+        self.inner.get_reachability().to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn core_distances_(&self) -> Vec<f64> {
+        self.inner.get_core_distances().to_vec()
+    }
+
+    /// The point each point was reached from, `-1` if unreachable.
+    #[wasm_bindgen]
+    pub fn predecessor_(&self) -> Vec<i32> {
+        labels_with_noise(self.inner.get_predecessor())
+    }
+
+    /// The clusters found by the Xi method, as ranges of positions in the ordering.
+    #[wasm_bindgen]
+    pub fn cluster_hierarchy_(&self) -> ClusterHierarchy {
+        ClusterHierarchy(self.inner.get_cluster_hierarchy().to_vec())
+    }
+
+    #[wasm_bindgen]
+    pub fn n_clusters_(&self) -> usize {
+        self.inner.get_n_clusters()
+    }
+}
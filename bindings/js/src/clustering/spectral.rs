@@ -0,0 +1,125 @@
+//! Bindings for the spectral clustering algorithm.
+
+use crate::clustering::kmeans::DistanceMetric;
+use crate::core::*;
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// How similar two points are in the graph whose cuts separate the clusters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify, Default)]
+#[serde(rename_all = "kebab-case")]
+#[tsify(from_wasm_abi)]
+pub enum Affinity {
+    #[default]
+    Rbf,
+    NearestNeighbors,
+}
+
+impl From<Affinity> for toymlrs_clustering::spectral::Affinity {
+    fn from(affinity: Affinity) -> Self {
+        match affinity {
+            Affinity::Rbf => toymlrs_clustering::spectral::Affinity::Rbf,
+            Affinity::NearestNeighbors => toymlrs_clustering::spectral::Affinity::NearestNeighbors,
+        }
+    }
+}
+
+/// The spectral clustering options.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(from_wasm_abi)]
+pub struct SpectralClusteringOptions {
+    /// Defaults to `2`
+    pub n_clusters: Option<usize>,
+    /// Defaults to `"rbf"`
+    #[serde(default)]
+    pub affinity: Affinity,
+    /// Scale of the RBF affinity `exp(-gamma * d²)`, defaults to `1`
+    pub gamma: Option<f64>,
+    /// Number of neighbours of each point for the `"nearest-neighbors"` affinity, defaults to
+    /// `10`
+    pub n_neighbors: Option<usize>,
+    /// Number of k-means runs on the embedding, defaults to `10`
+    pub n_init: Option<usize>,
+    /// Largest number of iterations of the eigenvector search, defaults to `1000`
+    pub max_iter: Option<usize>,
+    /// Defaults to `"euclidean"`, use `{ minkowski: p }` for the Minkowski distance
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+    pub random_seed: Option<u64>,
+}
+
+/// One row of unit length per point, one column per cluster.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Embedding(pub Vec<Vec<f64>>);
+
+/// A spectral clustering algorithm.
+#[derive(Debug)]
+#[wasm_bindgen]
+pub struct SpectralClustering {
+    inner: toymlrs_clustering::spectral::SpectralClustering,
+}
+
+#[wasm_bindgen]
+impl SpectralClustering {
+    /// Create a new SpectralClustering instance.
+    #[wasm_bindgen(constructor)]
+    pub fn new(opts: SpectralClusteringOptions) -> Self {
+        let mut inner = toymlrs_clustering::spectral::SpectralClustering::new(
+            opts.n_clusters.unwrap_or(2),
+            opts.affinity.into(),
+            opts.distance_metric.into(),
+            opts.random_seed,
+        );
+        if let Some(gamma) = opts.gamma {
+            inner.gamma = gamma;
+        }
+        if let Some(n_neighbors) = opts.n_neighbors {
+            inner.n_neighbors = n_neighbors;
+        }
+        if let Some(n_init) = opts.n_init {
+            inner.n_init = n_init;
+        }
+        if let Some(max_iter) = opts.max_iter {
+            inner.max_iter = max_iter;
+        }
+        Self { inner }
+    }
+
+    /// Cluster the given data points.
+    #[wasm_bindgen]
+    pub fn fit(&mut self, point_values: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit(point_values.convert()?)?)
+    }
+
+    /// Cluster from the square matrix of the non-negative affinities between the points.
+    #[wasm_bindgen]
+    pub fn fit_precomputed(&mut self, affinity: VecVecF64) -> Result<(), JsError> {
+        Ok(self.inner.fit_precomputed(affinity.convert()?)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn fit_predict(&mut self, point_values: VecVecF64) -> Result<Vec<usize>, JsError> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    #[wasm_bindgen]
+    pub fn labels_(&self) -> Vec<usize> {
+        self.inner.get_labels().to_vec()
+    }
+
+    /// The points as clustered by k-means.
+    #[wasm_bindgen]
+    pub fn embedding_(&self) -> Embedding {
+        Embedding(self.inner.get_embedding().to_vec())
+    }
+
+    /// The smallest eigenvalues of the normalized Laplacian, in increasing order.
+    #[wasm_bindgen]
+    pub fn eigenvalues_(&self) -> Vec<f64> {
+        self.inner.get_eigenvalues().to_vec()
+    }
+}
@@ -47,6 +47,14 @@ impl VecVecF64 {
     }
 }
 
+/// Labels of the algorithms flagging noise, with `-1` for noise.
+pub fn labels_with_noise(labels: &[Option<usize>]) -> Vec<i32> {
+    labels
+        .iter()
+        .map(|label| label.map_or(-1, |label| label as i32))
+        .collect()
+}
+
 #[wasm_bindgen]
 pub fn greet(name: &str) {
     alert(&format!("Hello, {}!", name));
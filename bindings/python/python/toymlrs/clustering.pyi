@@ -1,4 +1,9 @@
-from typing import Optional
+from typing import Optional, Union
+
+import numpy as np
+import numpy.typing as npt
+
+PointValues = Union[list[list[float]], npt.NDArray[np.float32], npt.NDArray[np.float64]]
 
 
 class Kmeans:
@@ -6,21 +11,337 @@ class Kmeans:
                  centroids_init_method: str = "random",
                  distance_metric: str = "euclidean",
                  random_seed: Optional[int] = None,
+                 tol: float = 1e-4,
+                 n_init: int = 1,
+                 empty_cluster_strategy: str = "farthest-point",
+                 algorithm: str = "lloyd",
+                 init_centroids: Optional[PointValues] = None,
                  ) -> None: ...
 
-    def fit(self, point_values: list[list[float]]) -> None: ...
+    def fit(self, point_values: PointValues,
+            sample_weight: Optional[list[float]] = None) -> None: ...
+
+    def fit_predict(self, point_values: PointValues,
+                    sample_weight: Optional[list[float]] = None) -> list[int]: ...
 
-    def fit_predict(self, point_values: list[list[float]]) -> list[int]: ...
+    def predict(self, point_values: PointValues) -> list[int]: ...
+
+    def transform(self, point_values: PointValues) -> list[list[float]]: ...
 
     @property
     def labels_(self) -> list[int]: ...
 
+    @property
+    def inertia_(self) -> float: ...
+
+    @property
+    def n_iter_(self) -> int: ...
+
+    @property
+    def n_init(self) -> int: ...
+
+    @property
+    def best_init_(self) -> int: ...
+
+    @property
+    def algorithm(self) -> str: ...
+
+    @property
+    def n_distances_(self) -> int: ...
+
     @property
     def centroids_(self) -> dict[int, list[float]]: ...
 
     def clusters_(self) -> dict[int, list[int]]: ...
 
+    @property
+    def dtype(self) -> str: ...
+
+
+class MiniBatchKmeans:
+    def __init__(self, k: int,
+                 batch_size: int = 1024,
+                 max_iter: int = 100,
+                 centroids_init_method: str = "kmeans++",
+                 distance_metric: str = "euclidean",
+                 random_seed: Optional[int] = None,
+                 tol: float = 0.0,
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def partial_fit(self, point_values: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    def predict(self, point_values: PointValues) -> list[int]: ...
+
+    def transform(self, point_values: PointValues) -> list[list[float]]: ...
+
+    @property
+    def labels_(self) -> list[int]: ...
+
+    @property
+    def inertia_(self) -> float: ...
+
+    @property
+    def n_steps_(self) -> int: ...
+
+    @property
+    def counts_(self) -> list[int]: ...
+
+    @property
+    def centroids_(self) -> dict[int, list[float]]: ...
+
+
+class Dbscan:
+    def __init__(self, eps: float = 0.5,
+                 min_samples: int = 5,
+                 distance_metric: str = "euclidean",
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    @property
+    def labels_(self) -> list[int]: ...
+
+    @property
+    def core_sample_indices_(self) -> list[int]: ...
+
+    @property
+    def n_clusters_(self) -> int: ...
+
+class Hdbscan:
+    def __init__(self, min_cluster_size: int = 5,
+                 min_samples: Optional[int] = None,
+                 cluster_selection_method: str = "eom",
+                 distance_metric: str = "euclidean",
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    @property
+    def labels_(self) -> list[int]: ...
+
+    @property
+    def probabilities_(self) -> list[float]: ...
+
+    @property
+    def outlier_scores_(self) -> list[float]: ...
+
+    @property
+    def condensed_tree_(self) -> list[tuple[int, int, float, int]]: ...
+
+    @property
+    def n_clusters_(self) -> int: ...
+
+class AgglomerativeClustering:
+    def __init__(self, n_clusters: Optional[int] = 2,
+                 distance_threshold: Optional[float] = None,
+                 linkage: str = "ward",
+                 distance_metric: str = "euclidean",
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    @property
+    def labels_(self) -> list[int]: ...
+
+    @property
+    def n_clusters_(self) -> int: ...
+
+    @property
+    def linkage_matrix_(self) -> list[list[float]]: ...
+
+class GaussianMixture:
+    def __init__(self, n_components: int = 1,
+                 covariance_type: str = "full",
+                 tol: float = 1e-3,
+                 reg_covar: float = 1e-6,
+                 max_iter: int = 100,
+                 n_init: int = 1,
+                 init_method: str = "kmeans++",
+                 random_seed: Optional[int] = None,
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    def predict(self, point_values: PointValues) -> list[int]: ...
+
+    def predict_proba(self, point_values: PointValues) -> list[list[float]]: ...
+
+    def score_samples(self, point_values: PointValues) -> list[float]: ...
+
+    def score(self, point_values: PointValues) -> float: ...
+
+    def bic(self, point_values: PointValues) -> float: ...
+
+    def aic(self, point_values: PointValues) -> float: ...
+
+    @property
+    def weights_(self) -> list[float]: ...
+
+    @property
+    def means_(self) -> list[list[float]]: ...
+
+    @property
+    def covariances_(self) -> list[list[float]]: ...
+
+    @property
+    def converged_(self) -> bool: ...
+
+    @property
+    def n_iter_(self) -> int: ...
+
+    @property
+    def lower_bound_(self) -> float: ...
+
+class Kmedoids:
+    def __init__(self, k: int, max_iter: int = 100,
+                 algorithm: str = "fasterpam",
+                 distance_metric: str = "euclidean",
+                 random_seed: Optional[int] = None,
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def fit_precomputed(self, distances: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    def predict(self, point_values: PointValues) -> list[int]: ...
+
+    @property
+    def labels_(self) -> list[int]: ...
+
+    @property
+    def medoid_indices_(self) -> list[int]: ...
+
+    @property
+    def inertia_(self) -> float: ...
+
+    @property
+    def n_iter_(self) -> int: ...
+
+
+class MeanShift:
+    def __init__(self, bandwidth: Optional[float] = None,
+                 kernel: str = "flat",
+                 bin_seeding: bool = False,
+                 min_bin_freq: int = 1,
+                 max_iter: int = 300,
+                 distance_metric: str = "euclidean",
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    def predict(self, point_values: PointValues) -> list[int]: ...
+
+    @property
+    def labels_(self) -> list[int]: ...
+
+    @property
+    def cluster_centers_(self) -> dict[int, list[float]]: ...
+
+    @property
+    def bandwidth_(self) -> float: ...
+
+    @property
+    def n_iter_(self) -> int: ...
+
+
+def estimate_bandwidth(point_values: PointValues, quantile: float = 0.3,
+                       distance_metric: str = "euclidean") -> float: ...
+
+
+class SpectralClustering:
+    def __init__(self, n_clusters: int = 2,
+                 affinity: str = "rbf",
+                 gamma: float = 1.0,
+                 n_neighbors: int = 10,
+                 n_init: int = 10,
+                 max_iter: int = 1000,
+                 distance_metric: str = "euclidean",
+                 random_seed: Optional[int] = None,
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def fit_precomputed(self, affinity: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    @property
+    def labels_(self) -> list[int]: ...
+
+    @property
+    def embedding_(self) -> list[list[float]]: ...
+
+    @property
+    def eigenvalues_(self) -> list[float]: ...
+
+
+class Optics:
+    def __init__(self, min_samples: int = 5,
+                 max_eps: float = float("inf"),
+                 cluster_method: str = "xi",
+                 eps: Optional[float] = None,
+                 xi: float = 0.05,
+                 min_cluster_size: Optional[int] = None,
+                 distance_metric: str = "euclidean",
+                 ) -> None: ...
+
+    def fit(self, point_values: PointValues) -> None: ...
+
+    def fit_predict(self, point_values: PointValues) -> list[int]: ...
+
+    def extract_dbscan(self, eps: float) -> list[int]: ...
+
+    def extract_xi(self, xi: float = 0.05, min_cluster_size: Optional[int] = None,
+                   ) -> tuple[list[int], npt.NDArray[np.uintp]]: ...
+
+    @property
+    def labels_(self) -> list[int]: ...
+
+    @property
+    def ordering_(self) -> npt.NDArray[np.uintp]: ...
+
+    @property
+    def reachability_(self) -> npt.NDArray[np.float64]: ...
+
+    @property
+    def core_distances_(self) -> npt.NDArray[np.float64]: ...
+
+    @property
+    def predecessor_(self) -> npt.NDArray[np.int64]: ...
+
+    @property
+    def cluster_hierarchy_(self) -> npt.NDArray[np.uintp]: ...
+
+    @property
+    def n_clusters_(self) -> int: ...
+
 
 __all__ = [
     "Kmeans",
+    "MiniBatchKmeans",
+    "Dbscan",
+    "Hdbscan",
+    "AgglomerativeClustering",
+    "GaussianMixture",
+    "Kmedoids",
+    "MeanShift",
+    "SpectralClustering",
+    "Optics",
+    "estimate_bandwidth",
 ]
@@ -1 +1,89 @@
+pub mod agglomerative;
+pub mod dbscan;
+pub mod gaussian_mixture;
+pub mod hdbscan;
 pub mod kmeans;
+pub mod kmedoids;
+pub mod mean_shift;
+pub mod mini_batch_kmeans;
+pub mod optics;
+pub mod spectral;
+
+use numpy::PyReadonlyArray2;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::PyErr;
+use toymlrs_clustering::dataset::{IntoPoints, Points};
+use toymlrs_clustering::Error;
+
+/// Convert a clustering error into a Python `ValueError`.
+pub(crate) fn value_error(e: toymlrs_clustering::Error) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// Labels of the algorithms flagging noise, with `-1` for noise like scikit-learn.
+pub(crate) fn labels_with_noise(labels: &[Option<usize>]) -> Vec<i64> {
+    labels
+        .iter()
+        .map(|label| label.map_or(-1, |label| label as i64))
+        .collect()
+}
+
+/// Convert the sample weights given by Python to the float type of the model.
+pub(crate) fn cast_weights<F: toymlrs_clustering::Float>(sample_weight: &[f64]) -> Vec<F> {
+    sample_weight.iter().map(|&w| F::from_f64(w)).collect()
+}
+
+/// Points given by Python, as a numpy array or nested lists.
+///
+/// float32 arrays are tried first so they are clustered without being upcast.
+#[derive(FromPyObject)]
+pub enum PointValues<'py> {
+    Float32(PyReadonlyArray2<'py, f32>),
+    Float64(PyReadonlyArray2<'py, f64>),
+    List(Vec<Vec<f64>>),
+}
+
+impl PointValues<'_> {
+    pub(crate) fn is_float32(&self) -> bool {
+        matches!(self, PointValues::Float32(_))
+    }
+}
+
+/// The float types a model can be fitted with, borrowing the point values when they already
+/// have the type.
+pub trait FromPointValues: toymlrs_clustering::Float {
+    fn points<'a>(point_values: &'a PointValues<'_>) -> Result<Points<'a, Self>, Error>;
+}
+
+impl FromPointValues for f32 {
+    fn points<'a>(point_values: &'a PointValues<'_>) -> Result<Points<'a, f32>, Error> {
+        match point_values {
+            PointValues::Float32(array) => array.as_array().into_points(),
+            PointValues::Float64(array) => {
+                let array = array.as_array();
+                Points::new(array.iter().map(|&x| x as f32).collect(), array.ncols())
+            }
+            PointValues::List(values) => {
+                let points = values.as_slice().into_points()?;
+                Points::new(
+                    points.as_slice().iter().map(|&x| x as f32).collect(),
+                    points.dim(),
+                )
+            }
+        }
+    }
+}
+
+impl FromPointValues for f64 {
+    fn points<'a>(point_values: &'a PointValues<'_>) -> Result<Points<'a, f64>, Error> {
+        match point_values {
+            PointValues::Float32(array) => {
+                let array = array.as_array();
+                Points::new(array.iter().map(|&x| f64::from(x)).collect(), array.ncols())
+            }
+            PointValues::Float64(array) => array.as_array().into_points(),
+            PointValues::List(values) => values.as_slice().into_points(),
+        }
+    }
+}
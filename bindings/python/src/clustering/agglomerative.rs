@@ -0,0 +1,79 @@
+use crate::clustering::{value_error, FromPointValues, PointValues};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use toymlrs_clustering::Error;
+
+#[derive(Debug)]
+#[pyclass]
+pub struct AgglomerativeClustering {
+    inner: toymlrs_clustering::agglomerative::AgglomerativeClustering,
+}
+
+#[pymethods]
+impl AgglomerativeClustering {
+    fn __repr__(&self) -> String {
+        format!(
+            "AgglomerativeClustering(n_clusters={}, distance_threshold={}, linkage={}, distance_metric={})",
+            self.inner
+                .n_clusters
+                .map_or("None".to_string(), |n_clusters| n_clusters.to_string()),
+            self.inner
+                .distance_threshold
+                .map_or("None".to_string(), |threshold| threshold.to_string()),
+            self.inner.linkage,
+            self.inner.distance_metric,
+        )
+    }
+
+    #[new]
+    #[pyo3(signature = (
+        n_clusters=Some(2),
+        distance_threshold=None,
+        linkage="ward",
+        distance_metric="euclidean",
+    ))]
+    fn py_new(
+        n_clusters: Option<usize>,
+        distance_threshold: Option<f64>,
+        linkage: &str,
+        distance_metric: &str,
+    ) -> PyResult<Self> {
+        let inner = toymlrs_clustering::agglomerative::AgglomerativeClustering::new(
+            n_clusters,
+            distance_threshold,
+            linkage
+                .parse()
+                .map_err(|_| PyValueError::new_err(format!("unknown linkage: {linkage}")))?,
+            distance_metric.parse().map_err(|_| {
+                value_error(Error::UnknownDistanceMetric(distance_metric.to_string()))
+            })?,
+        );
+        Ok(AgglomerativeClustering { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    #[getter]
+    pub fn labels_(&self) -> Vec<usize> {
+        self.inner.get_labels().to_vec()
+    }
+
+    #[getter]
+    pub fn n_clusters_(&self) -> usize {
+        self.inner.get_n_clusters()
+    }
+
+    /// Every merge as a SciPy linkage matrix, ready for `scipy.cluster.hierarchy.dendrogram`.
+    #[getter]
+    pub fn linkage_matrix_(&self) -> Vec<[f64; 4]> {
+        self.inner.get_linkage_matrix()
+    }
+}
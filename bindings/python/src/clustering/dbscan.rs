@@ -0,0 +1,58 @@
+use crate::clustering::{labels_with_noise, value_error, FromPointValues, PointValues};
+use pyo3::prelude::*;
+use toymlrs_clustering::Error;
+
+#[derive(Debug)]
+#[pyclass]
+pub struct Dbscan {
+    inner: toymlrs_clustering::dbscan::Dbscan,
+}
+
+#[pymethods]
+impl Dbscan {
+    fn __repr__(&self) -> String {
+        format!(
+            "Dbscan(eps={}, min_samples={}, distance_metric={})",
+            self.inner.eps, self.inner.min_samples, self.inner.distance_metric,
+        )
+    }
+
+    #[new]
+    #[pyo3(signature = (eps=0.5, min_samples=5, distance_metric="euclidean"))]
+    fn py_new(eps: f64, min_samples: usize, distance_metric: &str) -> PyResult<Self> {
+        let inner = toymlrs_clustering::dbscan::Dbscan::new(
+            eps,
+            min_samples,
+            distance_metric.parse().map_err(|_| {
+                value_error(Error::UnknownDistanceMetric(distance_metric.to_string()))
+            })?,
+        );
+        Ok(Dbscan { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<Vec<i64>> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// The cluster of each point, `-1` for noise.
+    #[getter]
+    pub fn labels_(&self) -> Vec<i64> {
+        labels_with_noise(self.inner.get_labels())
+    }
+
+    #[getter]
+    pub fn core_sample_indices_(&self) -> Vec<usize> {
+        self.inner.get_core_sample_indices().to_vec()
+    }
+
+    #[getter]
+    pub fn n_clusters_(&self) -> usize {
+        self.inner.get_n_clusters()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::clustering::{value_error, FromPointValues, PointValues};
+use pyo3::prelude::*;
+use toymlrs_clustering::Error;
+
+#[derive(Debug)]
+#[pyclass]
+pub struct GaussianMixture {
+    inner: toymlrs_clustering::gaussian_mixture::GaussianMixture,
+}
+
+#[pymethods]
+impl GaussianMixture {
+    fn __repr__(&self) -> String {
+        format!(
+            "GaussianMixture(n_components={}, covariance_type={}, max_iter={}, init_method={})",
+            self.inner.n_components,
+            self.inner.covariance_type,
+            self.inner.max_iter,
+            self.inner.init_method,
+        )
+    }
+
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (n_components=1, covariance_type="full", tol=1e-3, reg_covar=1e-6, max_iter=100, n_init=1, init_method="kmeans++", random_seed=None))]
+    fn py_new(
+        n_components: usize,
+        covariance_type: &str,
+        tol: f64,
+        reg_covar: f64,
+        max_iter: usize,
+        n_init: usize,
+        init_method: &str,
+        random_seed: Option<u64>,
+    ) -> PyResult<Self> {
+        let mut inner = toymlrs_clustering::gaussian_mixture::GaussianMixture::new(
+            n_components,
+            covariance_type.parse().map_err(|_| {
+                value_error(Error::InvalidParameter {
+                    name: "covariance_type".to_string(),
+                    reason: format!(
+                        "expected full, diag, spherical or tied, got {:?}",
+                        covariance_type
+                    ),
+                })
+            })?,
+            max_iter,
+            init_method.parse().map_err(|_| {
+                value_error(Error::UnknownCentroidsInitMethod(init_method.to_string()))
+            })?,
+            random_seed,
+        );
+        inner.tol = tol;
+        inner.reg_covar = reg_covar;
+        inner.n_init = n_init;
+        Ok(GaussianMixture { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        self.fit(point_values)?;
+        Ok(self.inner.get_labels().to_vec())
+    }
+
+    /// The most probable component of each point.
+    pub fn predict(&self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.predict(points).map_err(value_error)
+    }
+
+    /// Probability of each component for each point.
+    pub fn predict_proba(&self, point_values: PointValues) -> PyResult<Vec<Vec<f64>>> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.predict_proba(points).map_err(value_error)
+    }
+
+    /// Log-likelihood of each point under the model.
+    pub fn score_samples(&self, point_values: PointValues) -> PyResult<Vec<f64>> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.score_samples(points).map_err(value_error)
+    }
+
+    /// Mean log-likelihood of the points under the model.
+    pub fn score(&self, point_values: PointValues) -> PyResult<f64> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.score(points).map_err(value_error)
+    }
+
+    /// Bayesian information criterion on the points, the lower the better.
+    pub fn bic(&self, point_values: PointValues) -> PyResult<f64> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.bic(points).map_err(value_error)
+    }
+
+    /// Akaike information criterion on the points, the lower the better.
+    pub fn aic(&self, point_values: PointValues) -> PyResult<f64> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.aic(points).map_err(value_error)
+    }
+
+    #[getter]
+    pub fn weights_(&self) -> Vec<f64> {
+        self.inner.get_weights().to_vec()
+    }
+
+    #[getter]
+    pub fn means_(&self) -> Vec<Vec<f64>> {
+        self.inner.get_means().to_vec()
+    }
+
+    /// The covariances, flattened row-major for the full and tied covariance types.
+    #[getter]
+    pub fn covariances_(&self) -> Vec<Vec<f64>> {
+        self.inner.get_covariances().to_vec()
+    }
+
+    #[getter]
+    pub fn converged_(&self) -> bool {
+        self.inner.get_converged()
+    }
+
+    #[getter]
+    pub fn n_iter_(&self) -> usize {
+        self.inner.get_n_iter()
+    }
+
+    #[getter]
+    pub fn lower_bound_(&self) -> f64 {
+        self.inner.get_lower_bound()
+    }
+}
@@ -0,0 +1,96 @@
+use crate::clustering::{labels_with_noise, value_error, FromPointValues, PointValues};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use toymlrs_clustering::Error;
+
+#[derive(Debug)]
+#[pyclass]
+pub struct Hdbscan {
+    inner: toymlrs_clustering::hdbscan::Hdbscan,
+}
+
+#[pymethods]
+impl Hdbscan {
+    fn __repr__(&self) -> String {
+        format!(
+            "Hdbscan(min_cluster_size={}, min_samples={}, cluster_selection_method={}, distance_metric={})",
+            self.inner.min_cluster_size,
+            self.inner
+                .min_samples
+                .map_or("None".to_string(), |min_samples| min_samples.to_string()),
+            self.inner.cluster_selection_method,
+            self.inner.distance_metric,
+        )
+    }
+
+    #[new]
+    #[pyo3(signature = (
+        min_cluster_size=5,
+        min_samples=None,
+        cluster_selection_method="eom",
+        distance_metric="euclidean",
+    ))]
+    fn py_new(
+        min_cluster_size: usize,
+        min_samples: Option<usize>,
+        cluster_selection_method: &str,
+        distance_metric: &str,
+    ) -> PyResult<Self> {
+        let inner = toymlrs_clustering::hdbscan::Hdbscan::new(
+            min_cluster_size,
+            min_samples,
+            cluster_selection_method.parse().map_err(|_| {
+                PyValueError::new_err(format!(
+                    "unknown cluster selection method: {cluster_selection_method}"
+                ))
+            })?,
+            distance_metric.parse().map_err(|_| {
+                value_error(Error::UnknownDistanceMetric(distance_metric.to_string()))
+            })?,
+        );
+        Ok(Hdbscan { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<Vec<i64>> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// The cluster of each point, `-1` for noise.
+    #[getter]
+    pub fn labels_(&self) -> Vec<i64> {
+        labels_with_noise(self.inner.get_labels())
+    }
+
+    /// How strongly each point belongs to its cluster, `0` for noise.
+    #[getter]
+    pub fn probabilities_(&self) -> Vec<f64> {
+        self.inner.get_probabilities().to_vec()
+    }
+
+    /// GLOSH outlier score of each point, the higher the more of an outlier.
+    #[getter]
+    pub fn outlier_scores_(&self) -> Vec<f64> {
+        self.inner.get_outlier_scores().to_vec()
+    }
+
+    /// The condensed tree as `(parent, child, lambda, child_size)` tuples.
+    #[getter]
+    pub fn condensed_tree_(&self) -> Vec<(usize, usize, f64, usize)> {
+        self.inner
+            .get_condensed_tree()
+            .iter()
+            .map(|edge| (edge.parent, edge.child, edge.lambda, edge.child_size))
+            .collect()
+    }
+
+    #[getter]
+    pub fn n_clusters_(&self) -> usize {
+        self.inner.get_n_clusters()
+    }
+}
@@ -1,81 +1,284 @@
+use crate::clustering::{cast_weights, value_error, FromPointValues, PointValues};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyo3::IntoPyObjectExt;
 use std::collections::HashMap;
+use toymlrs_clustering::kmeans::{Centroids, Point};
+use toymlrs_clustering::{Error, Float};
+
+/// The k-means model, in the float type of the points it was last fitted with.
+#[derive(Debug)]
+enum Model {
+    Float64(toymlrs_clustering::kmeans::Kmeans<f64>),
+    Float32(toymlrs_clustering::kmeans::Kmeans<f32>),
+}
+
+/// Run `$body` with `$kmeans` bound to the model, whatever its float type.
+macro_rules! with_model {
+    ($model:expr, $kmeans:ident => $body:expr) => {
+        match $model {
+            Model::Float64($kmeans) => $body,
+            Model::Float32($kmeans) => $body,
+        }
+    };
+}
 
 #[derive(Debug)]
 #[pyclass]
 pub struct Kmeans {
-    inner: toymlrs_clustering::kmeans::Kmeans,
+    inner: Model,
+}
+
+impl Kmeans {
+    /// A model with the same hyperparameters, not fitted yet.
+    fn unfitted<F: Float>(&self) -> toymlrs_clustering::kmeans::Kmeans<F> {
+        with_model!(&self.inner, kmeans => {
+            let mut unfitted = toymlrs_clustering::kmeans::Kmeans::new(
+                kmeans.k,
+                kmeans.max_iter,
+                kmeans.centroids_init_method,
+                kmeans.distance_metric,
+                kmeans.random_seed,
+            );
+            unfitted.tol = F::from_f64(kmeans.tol.into_f64());
+            unfitted.n_init = kmeans.n_init;
+            unfitted.empty_cluster_strategy = kmeans.empty_cluster_strategy;
+            unfitted.algorithm = kmeans.algorithm;
+            unfitted.init_centroids = kmeans.init_centroids.as_ref().map(|init_centroids| {
+                Centroids {
+                    centroid_map: init_centroids
+                        .centroid_map
+                        .iter()
+                        .map(|(&index, centroid)| {
+                            let values = centroid
+                                .values
+                                .iter()
+                                .map(|&x| F::from_f64(x.into_f64()))
+                                .collect();
+                            (index, Point { values })
+                        })
+                        .collect(),
+                }
+            });
+            unfitted
+        })
+    }
 }
 
 #[pymethods]
 impl Kmeans {
     fn __repr__(&self) -> String {
-        format!(
+        with_model!(&self.inner, kmeans => format!(
             "Kmeans(k={}, max_iter={}, centroids_init_method={}, distance_metric={})",
-            self.inner.k,
-            self.inner.max_iter,
-            self.inner.centroids_init_method,
-            self.inner.distance_metric,
-        )
+            kmeans.k, kmeans.max_iter, kmeans.centroids_init_method, kmeans.distance_metric,
+        ))
     }
 
     #[new]
-    #[pyo3(signature = (k, max_iter=100, centroids_init_method="random", distance_metric="euclidean", random_seed=None))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (k, max_iter=100, centroids_init_method="random", distance_metric="euclidean", random_seed=None, tol=1e-4, n_init=1, empty_cluster_strategy="farthest-point", algorithm="lloyd", init_centroids=None))]
     fn py_new(
         k: usize,
         max_iter: usize,
         centroids_init_method: &str,
         distance_metric: &str,
         random_seed: Option<u64>,
+        tol: f64,
+        n_init: usize,
+        empty_cluster_strategy: &str,
+        algorithm: &str,
+        init_centroids: Option<PointValues>,
     ) -> PyResult<Self> {
+        let mut inner = toymlrs_clustering::kmeans::Kmeans::new(
+            k,
+            max_iter,
+            centroids_init_method.parse().map_err(|_| {
+                value_error(Error::UnknownCentroidsInitMethod(
+                    centroids_init_method.to_string(),
+                ))
+            })?,
+            distance_metric.parse().map_err(|_| {
+                value_error(Error::UnknownDistanceMetric(distance_metric.to_string()))
+            })?,
+            random_seed,
+        );
+        inner.tol = tol;
+        inner.n_init = n_init;
+        inner.empty_cluster_strategy = empty_cluster_strategy.parse().map_err(|_| {
+            value_error(Error::InvalidParameter {
+                name: "empty_cluster_strategy".to_string(),
+                reason: format!(
+                    "expected error, farthest-point or highest-sse, got {:?}",
+                    empty_cluster_strategy
+                ),
+            })
+        })?;
+        inner.algorithm = algorithm.parse().map_err(|_| {
+            value_error(Error::InvalidParameter {
+                name: "algorithm".to_string(),
+                reason: format!("expected lloyd, elkan or hamerly, got {:?}", algorithm),
+            })
+        })?;
+        if let Some(init_centroids) = &init_centroids {
+            let init_centroids = f64::points(init_centroids).map_err(value_error)?;
+            inner = inner
+                .with_init_centroids(init_centroids)
+                .map_err(value_error)?;
+        }
         Ok(Kmeans {
-            inner: toymlrs_clustering::kmeans::Kmeans::new(
-                k,
-                max_iter,
-                centroids_init_method
-                    .parse()
-                    .expect("Centroids method should be random or kmeans++"),
-                distance_metric
-                    .parse()
-                    .expect("Distance method should be euclidean"),
-                random_seed,
-            ),
+            inner: Model::Float64(inner),
         })
     }
 
-    pub fn fit(&mut self, point_values: Vec<Vec<f64>>) {
-        self.inner.fit(point_values);
+    /// Fit the model, in float32 for float32 numpy arrays and in float64 otherwise.
+    #[pyo3(signature = (point_values, sample_weight=None))]
+    pub fn fit(
+        &mut self,
+        point_values: PointValues,
+        sample_weight: Option<Vec<f64>>,
+    ) -> PyResult<()> {
+        self.inner = if point_values.is_float32() {
+            Model::Float32(self.unfitted())
+        } else {
+            Model::Float64(self.unfitted())
+        };
+        with_model!(&mut self.inner, kmeans => {
+            let points = FromPointValues::points(&point_values).map_err(value_error)?;
+            match &sample_weight {
+                Some(sample_weight) => kmeans.fit_weighted(points, &cast_weights(sample_weight)),
+                None => kmeans.fit(points),
+            }
+            .map_err(value_error)
+        })
     }
 
-    pub fn fit_predict(&mut self, point_values: Vec<Vec<f64>>) -> PyResult<&Vec<usize>> {
-        self.fit(point_values);
+    #[pyo3(signature = (point_values, sample_weight=None))]
+    pub fn fit_predict(
+        &mut self,
+        point_values: PointValues,
+        sample_weight: Option<Vec<f64>>,
+    ) -> PyResult<&Vec<usize>> {
+        self.fit(point_values, sample_weight)?;
         self.labels_()
     }
 
+    pub fn predict(&self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        with_model!(&self.inner, kmeans => {
+            let points = FromPointValues::points(&point_values).map_err(value_error)?;
+            kmeans
+                .predict(points)
+                .map(|labels| labels.0)
+                .map_err(value_error)
+        })
+    }
+
+    pub fn transform(&self, py: Python<'_>, point_values: PointValues) -> PyResult<PyObject> {
+        with_model!(&self.inner, kmeans => {
+            let points = FromPointValues::points(&point_values).map_err(value_error)?;
+            kmeans
+                .transform(points)
+                .map_err(value_error)?
+                .into_py_any(py)
+        })
+    }
+
     #[getter]
     pub fn labels_(&self) -> PyResult<&Vec<usize>> {
-        Ok(&self.inner.get_labels().0)
+        Ok(with_model!(&self.inner, kmeans => &kmeans.get_labels().0))
+    }
+
+    #[getter]
+    pub fn inertia_(&self) -> f64 {
+        with_model!(&self.inner, kmeans => kmeans.get_inertia().into_f64())
+    }
+
+    #[getter]
+    pub fn n_iter_(&self) -> usize {
+        with_model!(&self.inner, kmeans => kmeans.get_n_iter())
     }
 
     #[getter]
-    pub fn centroids_(&self) -> HashMap<usize, &Vec<f64>> {
-        HashMap::from_iter(
-            self.inner
+    pub fn n_init(&self) -> usize {
+        with_model!(&self.inner, kmeans => kmeans.n_init)
+    }
+
+    #[getter]
+    pub fn best_init_(&self) -> usize {
+        with_model!(&self.inner, kmeans => kmeans.get_best_init())
+    }
+
+    #[getter]
+    pub fn algorithm(&self) -> String {
+        with_model!(&self.inner, kmeans => kmeans.algorithm.to_string())
+    }
+
+    /// Number of distances computed to assign the points during the last fit.
+    #[getter]
+    pub fn n_distances_(&self) -> usize {
+        with_model!(&self.inner, kmeans => kmeans.get_n_distances())
+    }
+
+    #[getter]
+    pub fn centroids_(&self, py: Python<'_>) -> PyResult<PyObject> {
+        with_model!(&self.inner, kmeans => HashMap::<usize, &Vec<_>>::from_iter(
+            kmeans
                 .get_centroids()
                 .centroid_map
                 .iter()
                 .map(|(k, v)| (*k, &v.values)),
         )
+        .into_py_any(py))
     }
 
     #[getter]
     pub fn clusters_(&self) -> HashMap<usize, &Vec<usize>> {
-        HashMap::from_iter(
-            self.inner
+        with_model!(&self.inner, kmeans => HashMap::from_iter(
+            kmeans
                 .get_clusters()
                 .cluster_map
                 .iter()
                 .map(|(k, v)| (*k, &v.point_indices)),
-        )
+        ))
+    }
+
+    /// The float type of the fitted model, float32 when fitted with a float32 array.
+    #[getter]
+    pub fn dtype(&self) -> &'static str {
+        match self.inner {
+            Model::Float64(_) => "float64",
+            Model::Float32(_) => "float32",
+        }
+    }
+
+    /// `k` to create the instance `__setstate__` is called on when unpickling.
+    fn __getnewargs__(&self) -> (usize,) {
+        (with_model!(&self.inner, kmeans => kmeans.k),)
+    }
+
+    /// The float type and the model in the binary format, hyperparameters and fitted state.
+    fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<(&'static str, Bound<'py, PyBytes>)> {
+        let bytes = with_model!(&self.inner, kmeans => kmeans.to_bytes()).map_err(value_error)?;
+        Ok((self.dtype(), PyBytes::new(py, &bytes)))
+    }
+
+    fn __setstate__(&mut self, state: (String, Bound<'_, PyBytes>)) -> PyResult<()> {
+        let (dtype, bytes) = state;
+        self.inner = match dtype.as_str() {
+            "float32" => Model::Float32(
+                toymlrs_clustering::kmeans::Kmeans::from_bytes(bytes.as_bytes())
+                    .map_err(value_error)?,
+            ),
+            "float64" => Model::Float64(
+                toymlrs_clustering::kmeans::Kmeans::from_bytes(bytes.as_bytes())
+                    .map_err(value_error)?,
+            ),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unsupported dtype {dtype:?}, expected \"float32\" or \"float64\""
+                )))
+            }
+        };
+        Ok(())
     }
 }
@@ -0,0 +1,89 @@
+use crate::clustering::{value_error, FromPointValues, PointValues};
+use pyo3::prelude::*;
+use toymlrs_clustering::Error;
+
+#[derive(Debug)]
+#[pyclass]
+pub struct Kmedoids {
+    inner: toymlrs_clustering::kmedoids::Kmedoids,
+}
+
+#[pymethods]
+impl Kmedoids {
+    fn __repr__(&self) -> String {
+        format!(
+            "Kmedoids(k={}, max_iter={}, algorithm={}, distance_metric={})",
+            self.inner.k, self.inner.max_iter, self.inner.algorithm, self.inner.distance_metric,
+        )
+    }
+
+    #[new]
+    #[pyo3(signature = (k, max_iter=100, algorithm="fasterpam", distance_metric="euclidean", random_seed=None))]
+    fn py_new(
+        k: usize,
+        max_iter: usize,
+        algorithm: &str,
+        distance_metric: &str,
+        random_seed: Option<u64>,
+    ) -> PyResult<Self> {
+        let inner = toymlrs_clustering::kmedoids::Kmedoids::new(
+            k,
+            max_iter,
+            algorithm.parse().map_err(|_| {
+                value_error(Error::InvalidParameter {
+                    name: "algorithm".to_string(),
+                    reason: format!("expected pam or fasterpam, got {:?}", algorithm),
+                })
+            })?,
+            distance_metric.parse().map_err(|_| {
+                value_error(Error::UnknownDistanceMetric(distance_metric.to_string()))
+            })?,
+            random_seed,
+        );
+        Ok(Kmedoids { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    /// Fit from the square matrix of the distances between the points.
+    pub fn fit_precomputed(&mut self, distances: PointValues) -> PyResult<()> {
+        let distances = f64::points(&distances).map_err(value_error)?;
+        self.inner.fit_precomputed(distances).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// Assign each point to its nearest medoid, for a model fitted with `fit`.
+    pub fn predict(&self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.predict(points).map_err(value_error)
+    }
+
+    #[getter]
+    pub fn labels_(&self) -> Vec<usize> {
+        self.inner.get_labels().to_vec()
+    }
+
+    /// Index in the dataset of the medoid of each cluster.
+    #[getter]
+    pub fn medoid_indices_(&self) -> Vec<usize> {
+        self.inner.get_medoid_indices().to_vec()
+    }
+
+    /// Sum of the distances from each point to its medoid.
+    #[getter]
+    pub fn inertia_(&self) -> f64 {
+        self.inner.get_inertia()
+    }
+
+    #[getter]
+    pub fn n_iter_(&self) -> usize {
+        self.inner.get_n_iter()
+    }
+}
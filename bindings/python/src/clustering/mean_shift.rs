@@ -0,0 +1,123 @@
+use crate::clustering::{value_error, FromPointValues, PointValues};
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use toymlrs_clustering::distance::DistanceMetric;
+use toymlrs_clustering::Error;
+
+fn parse_distance_metric(distance_metric: &str) -> PyResult<DistanceMetric> {
+    distance_metric
+        .parse()
+        .map_err(|_| value_error(Error::UnknownDistanceMetric(distance_metric.to_string())))
+}
+
+#[derive(Debug)]
+#[pyclass]
+pub struct MeanShift {
+    inner: toymlrs_clustering::mean_shift::MeanShift,
+}
+
+#[pymethods]
+impl MeanShift {
+    fn __repr__(&self) -> String {
+        format!(
+            "MeanShift(bandwidth={}, kernel={}, bin_seeding={}, distance_metric={})",
+            self.inner
+                .bandwidth
+                .map_or("None".to_string(), |bandwidth| bandwidth.to_string()),
+            self.inner.kernel,
+            if self.inner.bin_seeding {
+                "True"
+            } else {
+                "False"
+            },
+            self.inner.distance_metric,
+        )
+    }
+
+    #[new]
+    #[pyo3(signature = (bandwidth=None, kernel="flat", bin_seeding=false, min_bin_freq=1, max_iter=300, distance_metric="euclidean"))]
+    fn py_new(
+        bandwidth: Option<f64>,
+        kernel: &str,
+        bin_seeding: bool,
+        min_bin_freq: usize,
+        max_iter: usize,
+        distance_metric: &str,
+    ) -> PyResult<Self> {
+        let mut inner = toymlrs_clustering::mean_shift::MeanShift::new(
+            bandwidth,
+            kernel.parse().map_err(|_| {
+                value_error(Error::InvalidParameter {
+                    name: "kernel".to_string(),
+                    reason: format!("expected flat or gaussian, got {:?}", kernel),
+                })
+            })?,
+            bin_seeding,
+            parse_distance_metric(distance_metric)?,
+        );
+        inner.min_bin_freq = min_bin_freq;
+        inner.max_iter = max_iter;
+        Ok(MeanShift { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// Assign each point to its nearest cluster center.
+    pub fn predict(&self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.predict(points).map_err(value_error)
+    }
+
+    #[getter]
+    pub fn labels_(&self) -> Vec<usize> {
+        self.inner.get_labels().to_vec()
+    }
+
+    /// The modes of the density, keyed by label like `Kmeans.centroids_`.
+    #[getter]
+    pub fn cluster_centers_(&self) -> HashMap<usize, &Vec<f64>> {
+        self.inner
+            .get_cluster_centers()
+            .centroid_map
+            .iter()
+            .map(|(k, v)| (*k, &v.values))
+            .collect()
+    }
+
+    /// The bandwidth used by the last fit, given or estimated.
+    #[getter]
+    pub fn bandwidth_(&self) -> f64 {
+        self.inner.get_bandwidth()
+    }
+
+    #[getter]
+    pub fn n_iter_(&self) -> usize {
+        self.inner.get_n_iter()
+    }
+}
+
+/// Estimate a bandwidth for `MeanShift`, the mean distance from each point to its
+/// `quantile * n_samples`-th nearest point.
+#[pyfunction]
+#[pyo3(signature = (point_values, quantile=0.3, distance_metric="euclidean"))]
+pub fn estimate_bandwidth(
+    point_values: PointValues,
+    quantile: f64,
+    distance_metric: &str,
+) -> PyResult<f64> {
+    let points = f64::points(&point_values).map_err(value_error)?;
+    toymlrs_clustering::mean_shift::estimate_bandwidth(
+        points,
+        quantile,
+        &parse_distance_metric(distance_metric)?,
+    )
+    .map_err(value_error)
+}
@@ -0,0 +1,113 @@
+use crate::clustering::{value_error, FromPointValues, PointValues};
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use toymlrs_clustering::Error;
+
+#[derive(Debug)]
+#[pyclass]
+pub struct MiniBatchKmeans {
+    inner: toymlrs_clustering::mini_batch_kmeans::MiniBatchKmeans,
+}
+
+#[pymethods]
+impl MiniBatchKmeans {
+    fn __repr__(&self) -> String {
+        format!(
+            "MiniBatchKmeans(k={}, batch_size={}, max_iter={}, centroids_init_method={}, distance_metric={})",
+            self.inner.k,
+            self.inner.batch_size,
+            self.inner.max_iter,
+            self.inner.centroids_init_method,
+            self.inner.distance_metric,
+        )
+    }
+
+    #[new]
+    #[pyo3(signature = (k, batch_size=1024, max_iter=100, centroids_init_method="kmeans++", distance_metric="euclidean", random_seed=None, tol=0.0))]
+    fn py_new(
+        k: usize,
+        batch_size: usize,
+        max_iter: usize,
+        centroids_init_method: &str,
+        distance_metric: &str,
+        random_seed: Option<u64>,
+        tol: f64,
+    ) -> PyResult<Self> {
+        let mut inner = toymlrs_clustering::mini_batch_kmeans::MiniBatchKmeans::new(
+            k,
+            batch_size,
+            max_iter,
+            centroids_init_method.parse().map_err(|_| {
+                value_error(Error::UnknownCentroidsInitMethod(
+                    centroids_init_method.to_string(),
+                ))
+            })?,
+            distance_metric.parse().map_err(|_| {
+                value_error(Error::UnknownDistanceMetric(distance_metric.to_string()))
+            })?,
+            random_seed,
+        );
+        inner.tol = tol;
+        Ok(MiniBatchKmeans { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    /// Update the centroids with one more chunk of points.
+    pub fn partial_fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.partial_fit(points).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<&Vec<usize>> {
+        self.fit(point_values)?;
+        self.labels_()
+    }
+
+    pub fn predict(&self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner
+            .predict(points)
+            .map(|labels| labels.0)
+            .map_err(value_error)
+    }
+
+    pub fn transform(&self, point_values: PointValues) -> PyResult<Vec<Vec<f64>>> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.transform(points).map_err(value_error)
+    }
+
+    #[getter]
+    pub fn labels_(&self) -> PyResult<&Vec<usize>> {
+        Ok(&self.inner.get_labels().0)
+    }
+
+    #[getter]
+    pub fn inertia_(&self) -> f64 {
+        self.inner.get_inertia()
+    }
+
+    #[getter]
+    pub fn n_steps_(&self) -> usize {
+        self.inner.get_n_steps()
+    }
+
+    #[getter]
+    pub fn counts_(&self) -> Vec<usize> {
+        self.inner.get_counts().to_vec()
+    }
+
+    #[getter]
+    pub fn centroids_(&self) -> HashMap<usize, &Vec<f64>> {
+        HashMap::from_iter(
+            self.inner
+                .get_centroids()
+                .centroid_map
+                .iter()
+                .map(|(k, v)| (*k, &v.values)),
+        )
+    }
+}
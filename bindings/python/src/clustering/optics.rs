@@ -0,0 +1,144 @@
+use crate::clustering::{labels_with_noise, value_error, FromPointValues, PointValues};
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use pyo3::prelude::*;
+use toymlrs_clustering::Error;
+
+/// The ranges of positions in the ordering as a `(n_clusters, 2)` array.
+fn hierarchy_array<'py>(
+    py: Python<'py>,
+    hierarchy: &[(usize, usize)],
+) -> Bound<'py, PyArray2<usize>> {
+    let values = hierarchy
+        .iter()
+        .flat_map(|&(start, end)| [start, end])
+        .collect();
+    Array2::from_shape_vec((hierarchy.len(), 2), values)
+        .expect("two values per cluster")
+        .into_pyarray(py)
+}
+
+#[derive(Debug)]
+#[pyclass]
+pub struct Optics {
+    inner: toymlrs_clustering::optics::Optics,
+}
+
+#[pymethods]
+impl Optics {
+    fn __repr__(&self) -> String {
+        format!(
+            "Optics(min_samples={}, max_eps={}, cluster_method={}, distance_metric={})",
+            self.inner.min_samples,
+            self.inner.max_eps,
+            self.inner.cluster_method,
+            self.inner.distance_metric,
+        )
+    }
+
+    #[new]
+    #[pyo3(signature = (min_samples=5, max_eps=f64::INFINITY, cluster_method="xi", eps=None, xi=0.05, min_cluster_size=None, distance_metric="euclidean"))]
+    fn py_new(
+        min_samples: usize,
+        max_eps: f64,
+        cluster_method: &str,
+        eps: Option<f64>,
+        xi: f64,
+        min_cluster_size: Option<usize>,
+        distance_metric: &str,
+    ) -> PyResult<Self> {
+        let mut inner = toymlrs_clustering::optics::Optics::new(
+            min_samples,
+            max_eps,
+            cluster_method.parse().map_err(|_| {
+                value_error(Error::InvalidParameter {
+                    name: "cluster_method".to_string(),
+                    reason: format!("expected xi or dbscan, got {:?}", cluster_method),
+                })
+            })?,
+            distance_metric.parse().map_err(|_| {
+                value_error(Error::UnknownDistanceMetric(distance_metric.to_string()))
+            })?,
+        );
+        inner.eps = eps;
+        inner.xi = xi;
+        inner.min_cluster_size = min_cluster_size;
+        Ok(Optics { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<Vec<i64>> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    /// The clusters DBSCAN would find with `eps`, `-1` for noise, without refitting.
+    pub fn extract_dbscan(&self, eps: f64) -> PyResult<Vec<i64>> {
+        let labels = self.inner.extract_dbscan(eps).map_err(value_error)?;
+        Ok(labels_with_noise(&labels))
+    }
+
+    /// The clusters found by the Xi method, `-1` for noise, and the hierarchy of all the
+    /// clusters as ranges of positions in the ordering, without refitting.
+    #[pyo3(signature = (xi=0.05, min_cluster_size=None))]
+    pub fn extract_xi<'py>(
+        &self,
+        py: Python<'py>,
+        xi: f64,
+        min_cluster_size: Option<usize>,
+    ) -> PyResult<(Vec<i64>, Bound<'py, PyArray2<usize>>)> {
+        let clusters = self
+            .inner
+            .extract_xi(xi, min_cluster_size.unwrap_or(self.inner.min_samples))
+            .map_err(value_error)?;
+        Ok((
+            labels_with_noise(&clusters.labels),
+            hierarchy_array(py, &clusters.hierarchy),
+        ))
+    }
+
+    /// The cluster of each point, `-1` for noise.
+    #[getter]
+    pub fn labels_(&self) -> Vec<i64> {
+        labels_with_noise(self.inner.get_labels())
+    }
+
+    /// The points in the order they were visited.
+    #[getter]
+    pub fn ordering_<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<usize>> {
+        PyArray1::from_slice(py, self.inner.get_ordering())
+    }
+
+    /// The reachability distance of each point, indexed by point, the reachability plot being
+    /// `reachability_[ordering_]`.
+    #[getter]
+    pub fn reachability_<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, self.inner.get_reachability())
+    }
+
+    #[getter]
+    pub fn core_distances_<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, self.inner.get_core_distances())
+    }
+
+    /// The point each point was reached from, `-1` if unreachable.
+    #[getter]
+    pub fn predecessor_<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<i64>> {
+        labels_with_noise(self.inner.get_predecessor()).into_pyarray(py)
+    }
+
+    /// The clusters found by the Xi method, as ranges of positions in the ordering.
+    #[getter]
+    pub fn cluster_hierarchy_<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<usize>> {
+        hierarchy_array(py, self.inner.get_cluster_hierarchy())
+    }
+
+    #[getter]
+    pub fn n_clusters_(&self) -> usize {
+        self.inner.get_n_clusters()
+    }
+}
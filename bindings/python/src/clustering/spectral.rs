@@ -0,0 +1,89 @@
+use crate::clustering::{value_error, FromPointValues, PointValues};
+use pyo3::prelude::*;
+use toymlrs_clustering::Error;
+
+#[derive(Debug)]
+#[pyclass]
+pub struct SpectralClustering {
+    inner: toymlrs_clustering::spectral::SpectralClustering,
+}
+
+#[pymethods]
+impl SpectralClustering {
+    fn __repr__(&self) -> String {
+        format!(
+            "SpectralClustering(n_clusters={}, affinity={}, gamma={}, n_neighbors={}, distance_metric={})",
+            self.inner.n_clusters,
+            self.inner.affinity,
+            self.inner.gamma,
+            self.inner.n_neighbors,
+            self.inner.distance_metric,
+        )
+    }
+
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (n_clusters=2, affinity="rbf", gamma=1.0, n_neighbors=10, n_init=10, max_iter=1000, distance_metric="euclidean", random_seed=None))]
+    fn py_new(
+        n_clusters: usize,
+        affinity: &str,
+        gamma: f64,
+        n_neighbors: usize,
+        n_init: usize,
+        max_iter: usize,
+        distance_metric: &str,
+        random_seed: Option<u64>,
+    ) -> PyResult<Self> {
+        let mut inner = toymlrs_clustering::spectral::SpectralClustering::new(
+            n_clusters,
+            affinity.parse().map_err(|_| {
+                value_error(Error::InvalidParameter {
+                    name: "affinity".to_string(),
+                    reason: format!("expected rbf or nearest-neighbors, got {:?}", affinity),
+                })
+            })?,
+            distance_metric.parse().map_err(|_| {
+                value_error(Error::UnknownDistanceMetric(distance_metric.to_string()))
+            })?,
+            random_seed,
+        );
+        inner.gamma = gamma;
+        inner.n_neighbors = n_neighbors;
+        inner.n_init = n_init;
+        inner.max_iter = max_iter;
+        Ok(SpectralClustering { inner })
+    }
+
+    pub fn fit(&mut self, point_values: PointValues) -> PyResult<()> {
+        let points = f64::points(&point_values).map_err(value_error)?;
+        self.inner.fit(points).map_err(value_error)
+    }
+
+    /// Fit from the square matrix of the non-negative affinities between the points.
+    pub fn fit_precomputed(&mut self, affinity: PointValues) -> PyResult<()> {
+        let affinity = f64::points(&affinity).map_err(value_error)?;
+        self.inner.fit_precomputed(affinity).map_err(value_error)
+    }
+
+    pub fn fit_predict(&mut self, point_values: PointValues) -> PyResult<Vec<usize>> {
+        self.fit(point_values)?;
+        Ok(self.labels_())
+    }
+
+    #[getter]
+    pub fn labels_(&self) -> Vec<usize> {
+        self.inner.get_labels().to_vec()
+    }
+
+    /// The points as clustered by k-means, one row of unit length per point.
+    #[getter]
+    pub fn embedding_(&self) -> Vec<Vec<f64>> {
+        self.inner.get_embedding().to_vec()
+    }
+
+    /// The smallest eigenvalues of the normalized Laplacian, in increasing order.
+    #[getter]
+    pub fn eigenvalues_(&self) -> Vec<f64> {
+        self.inner.get_eigenvalues().to_vec()
+    }
+}
@@ -17,6 +17,19 @@ fn _toymlrs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Create the clustering submodule
     let clustering_module = PyModule::new(m.py(), "clustering")?;
     let _ = clustering_module.add_class::<clustering::kmeans::Kmeans>();
+    let _ = clustering_module.add_class::<clustering::mini_batch_kmeans::MiniBatchKmeans>();
+    let _ = clustering_module.add_class::<clustering::dbscan::Dbscan>();
+    let _ = clustering_module.add_class::<clustering::hdbscan::Hdbscan>();
+    let _ = clustering_module.add_class::<clustering::agglomerative::AgglomerativeClustering>();
+    let _ = clustering_module.add_class::<clustering::gaussian_mixture::GaussianMixture>();
+    let _ = clustering_module.add_class::<clustering::kmedoids::Kmedoids>();
+    let _ = clustering_module.add_class::<clustering::mean_shift::MeanShift>();
+    let _ = clustering_module.add_class::<clustering::spectral::SpectralClustering>();
+    let _ = clustering_module.add_class::<clustering::optics::Optics>();
+    clustering_module.add_function(wrap_pyfunction!(
+        clustering::mean_shift::estimate_bandwidth,
+        &clustering_module
+    )?)?;
     m.add_submodule(&clustering_module)?;
     m.py()
         .import("sys")?
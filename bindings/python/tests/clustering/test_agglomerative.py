@@ -0,0 +1,49 @@
+import numpy as np
+import pytest
+
+from toymlrs.clustering import AgglomerativeClustering
+
+
+class TestAgglomerativeClustering:
+    """
+    Test the agglomerative clustering algorithm.
+    """
+
+    dataset = [[0.0], [1.0], [3.0], [7.0], [8.0]]
+
+    def test_repr(self) -> None:
+        assert repr(AgglomerativeClustering(None, 1.5, "single")) == (
+            "AgglomerativeClustering(n_clusters=None, distance_threshold=1.5, linkage=single, distance_metric=euclidean)"
+        )
+
+    def test_fit_predict(self) -> None:
+        agglomerative = AgglomerativeClustering(3, linkage="complete")
+
+        assert agglomerative.fit_predict(np.array(self.dataset)) == [0, 0, 1, 2, 2]
+        assert agglomerative.n_clusters_ == 3
+
+    def test_distance_threshold(self) -> None:
+        agglomerative = AgglomerativeClustering(None, 2.5, "single")
+
+        assert agglomerative.fit_predict(self.dataset) == [0, 0, 0, 1, 1]
+        assert agglomerative.n_clusters_ == 2
+
+    def test_linkage_matrix(self) -> None:
+        agglomerative = AgglomerativeClustering(linkage="single")
+        agglomerative.fit(self.dataset)
+
+        # Same as scipy.cluster.hierarchy.linkage(dataset, "single")
+        assert agglomerative.linkage_matrix_ == [
+            [0.0, 1.0, 1.0, 2.0],
+            [3.0, 4.0, 1.0, 2.0],
+            [2.0, 5.0, 2.0, 3.0],
+            [6.0, 7.0, 4.0, 5.0],
+        ]
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="unknown linkage"):
+            AgglomerativeClustering(linkage="foo")
+        with pytest.raises(ValueError, match="exactly one"):
+            AgglomerativeClustering(2, 1.0).fit(self.dataset)
+        with pytest.raises(ValueError, match="k must be"):
+            AgglomerativeClustering(6).fit(self.dataset)
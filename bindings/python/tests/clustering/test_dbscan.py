@@ -0,0 +1,45 @@
+import math
+
+import numpy as np
+import pytest
+
+from toymlrs.clustering import Dbscan
+
+
+class TestDbscan:
+    """
+    Test the DBSCAN algorithm.
+    """
+
+    dataset = [
+        [1.0, 1.0], [1.2, 1.0], [1.0, 1.2], [1.1, 1.1],
+        [5.0, 5.0], [5.2, 5.0], [5.0, 5.2], [5.0, 5.6],
+        [9.0, 1.0],
+    ]
+
+    def test_repr(self) -> None:
+        assert repr(Dbscan(0.3, 4)) == "Dbscan(eps=0.3, min_samples=4, distance_metric=euclidean)"
+
+    def test_fit_predict(self) -> None:
+        dbscan = Dbscan(0.5, 3)
+        labels = dbscan.fit_predict(np.array(self.dataset))
+
+        assert labels == [0, 0, 0, 0, 1, 1, 1, 1, -1]
+        assert dbscan.core_sample_indices_ == [0, 1, 2, 3, 4, 5, 6]
+        assert dbscan.n_clusters_ == 2
+
+    def test_rings(self) -> None:
+        radii = [1.0 if i % 2 == 0 else 4.0 for i in range(120)]
+        rings = [[r * math.cos(i * math.pi / 30), r * math.sin(i * math.pi / 30)] for i, r in enumerate(radii)]
+        labels = Dbscan(0.9, 3).fit_predict(rings)
+
+        assert labels == [labels[i % 2] for i in range(120)]
+        assert labels[0] != labels[1]
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="unknown distance metric"):
+            Dbscan(0.5, 3, "foo")
+        with pytest.raises(ValueError, match="eps"):
+            Dbscan(-1.0).fit(self.dataset)
+        with pytest.raises(ValueError, match="empty"):
+            Dbscan().fit([])
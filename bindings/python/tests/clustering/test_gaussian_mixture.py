@@ -0,0 +1,66 @@
+import math
+
+import numpy as np
+import pytest
+
+from toymlrs.clustering import GaussianMixture
+
+
+class TestGaussianMixture:
+    """
+    Test the Gaussian mixture model.
+    """
+
+    rng = np.random.default_rng(42)
+    dataset = np.concatenate([
+        rng.normal(0.0, 1.0, (100, 2)),
+        rng.normal((10.0, 5.0), 0.5, (50, 2)),
+    ])
+    # Centred on the origin, with variances 2 and 4 and a covariance of 4/3
+    correlated = [[1.0, 2.0], [-1.0, -2.0], [1.0, -2.0], [-1.0, 2.0], [2.0, 2.0], [-2.0, -2.0]]
+
+    def test_repr(self) -> None:
+        assert repr(GaussianMixture(3, "diag")) == (
+            "GaussianMixture(n_components=3, covariance_type=diag, max_iter=100, init_method=kmeans++)"
+        )
+
+    @pytest.mark.parametrize("covariance_type", ["full", "diag", "spherical", "tied"])
+    def test_fit_predict(self, covariance_type: str) -> None:
+        gmm = GaussianMixture(2, covariance_type, random_seed=42)
+        labels = gmm.fit_predict(self.dataset)
+
+        assert len(set(labels[:100])) == 1 and len(set(labels[100:])) == 1
+        assert labels[0] != labels[100]
+        assert gmm.converged_
+        assert gmm.weights_[labels[0]] == pytest.approx(2 / 3, abs=1e-3)
+        assert gmm.means_[labels[100]] == pytest.approx([10.0, 5.0], abs=0.2)
+        assert gmm.predict([[0.5, 0.5], [9.5, 5.5]]) == [labels[0], labels[100]]
+        assert sum(gmm.predict_proba([[5.0, 2.5]])[0]) == pytest.approx(1.0)
+
+    def test_covariances(self) -> None:
+        gmm = GaussianMixture(reg_covar=0.0)
+        gmm.fit(self.correlated)
+
+        assert gmm.covariances_[0] == pytest.approx([2.0, 4 / 3, 4 / 3, 4.0])
+        expected = -(2 * math.log(2 * math.pi) + math.log(56 / 9)) / 2
+        assert gmm.score_samples([[0.0, 0.0]])[0] == pytest.approx(expected)
+
+    def test_model_selection(self) -> None:
+        scores = []
+        for k in range(1, 5):
+            gmm = GaussianMixture(k, n_init=3, random_seed=7)
+            gmm.fit(self.dataset)
+            scores.append(gmm.bic(self.dataset))
+            assert gmm.aic(self.dataset) < gmm.bic(self.dataset)
+
+        assert scores.index(min(scores)) == 1
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="covariance_type"):
+            GaussianMixture(covariance_type="foo")
+        with pytest.raises(ValueError, match="unknown centroids init method"):
+            GaussianMixture(init_method="foo")
+        with pytest.raises(ValueError, match="not fitted"):
+            GaussianMixture().predict(self.correlated)
+        with pytest.raises(ValueError, match="ill-defined covariance"):
+            GaussianMixture(reg_covar=0.0).fit([[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]])
@@ -0,0 +1,64 @@
+import math
+
+import numpy as np
+import pytest
+
+from toymlrs.clustering import Hdbscan
+
+
+def disc(centre: tuple[float, float], n: int, spacing: float) -> list[list[float]]:
+    golden_angle = math.pi * (3 - math.sqrt(5))
+    return [
+        [centre[0] + spacing * math.sqrt(i + 0.5) * math.cos(i * golden_angle),
+         centre[1] + spacing * math.sqrt(i + 0.5) * math.sin(i * golden_angle)]
+        for i in range(n)
+    ]
+
+
+class TestHdbscan:
+    """
+    Test the HDBSCAN algorithm.
+    """
+
+    # A dense and a sparse disc, then two outliers
+    dataset = disc((0.0, 0.0), 25, 0.1) + disc((10.0, 10.0), 25, 1.0) + [[40.0, -20.0], [-30.0, 30.0]]
+
+    def test_repr(self) -> None:
+        assert repr(Hdbscan(4)) == (
+            "Hdbscan(min_cluster_size=4, min_samples=None, cluster_selection_method=eom, distance_metric=euclidean)"
+        )
+
+    def test_fit_predict(self) -> None:
+        hdbscan = Hdbscan()
+        labels = hdbscan.fit_predict(np.array(self.dataset))
+
+        assert labels == [0] * 25 + [1] * 25 + [-1, -1]
+        assert hdbscan.n_clusters_ == 2
+        assert hdbscan.probabilities_[0] == 1.0
+        assert hdbscan.probabilities_[50:] == [0.0, 0.0]
+        assert all(score > 0.9 for score in hdbscan.outlier_scores_[50:])
+
+    def test_condensed_tree(self) -> None:
+        hdbscan = Hdbscan()
+        hdbscan.fit(self.dataset)
+        points = sorted(child for _, child, _, _ in hdbscan.condensed_tree_ if child < len(self.dataset))
+
+        assert points == list(range(len(self.dataset)))
+
+    def test_leaf_selection(self) -> None:
+        dataset = disc((0.0, 0.0), 16, 0.1) + disc((1.0, 0.0), 16, 0.1) + disc((20.0, 20.0), 16, 0.1)
+
+        assert Hdbscan().fit_predict(dataset) == [0] * 32 + [1] * 16
+        labels = Hdbscan(cluster_selection_method="leaf").fit_predict(dataset)
+        assert labels == [labels[0]] * 16 + [labels[16]] * 16 + [labels[32]] * 16
+        assert sorted({labels[0], labels[16], labels[32]}) == [0, 1, 2]
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="unknown distance metric"):
+            Hdbscan(distance_metric="foo")
+        with pytest.raises(ValueError, match="unknown cluster selection method"):
+            Hdbscan(cluster_selection_method="foo")
+        with pytest.raises(ValueError, match="min_cluster_size"):
+            Hdbscan(1).fit(self.dataset)
+        with pytest.raises(ValueError, match="empty"):
+            Hdbscan().fit([])
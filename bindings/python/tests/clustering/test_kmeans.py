@@ -1,3 +1,6 @@
+import pickle
+
+import numpy as np
 import pytest
 
 from toymlrs.clustering import Kmeans
@@ -12,6 +15,8 @@ class TestKMeansSimple:
                              [
                                  (2, 10, "random", "euclidean"),
                                  (5, 100, "kmeans++", "euclidean"),
+                                 (3, 100, "greedy-kmeans++", "euclidean"),
+                                 (3, 100, "kmeans||", "manhattan"),
                              ],
                              )
     def test_repr(self,
@@ -51,3 +56,252 @@ class TestKMeansSimple:
     #     # check prediction
     #     x_sample = [0.0] * len(dataset[0])
     #     assert kmeans.predict(x_sample) == first_sample_cluster
+
+
+class TestKMeansPredict:
+    """
+    Test predicting with a fitted k-means model.
+    """
+
+    dataset = [[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [10.0, 0.0], [10.0, 1.0], [10.0, 2.0]]
+
+    def test_predict(self) -> None:
+        kmeans = Kmeans(2, 100, "kmeans++", random_seed=42)
+        labels = kmeans.fit_predict(self.dataset)
+
+        assert kmeans.predict([[0.0, 1.0], [11.0, 1.0]]) == [labels[0], labels[-1]]
+        assert kmeans.predict(self.dataset) == labels
+
+    def test_transform(self) -> None:
+        kmeans = Kmeans(2, 100, "kmeans++", random_seed=42)
+        kmeans.fit(self.dataset)
+
+        distances = kmeans.transform([[0.0, 1.0]])
+        assert len(distances) == 1
+        assert len(distances[0]) == 2
+
+    def test_inertia_and_n_iter(self) -> None:
+        kmeans = Kmeans(2, 100, "kmeans++", random_seed=42)
+        kmeans.fit(self.dataset)
+
+        assert kmeans.inertia_ == pytest.approx(4.0)
+        assert 1 <= kmeans.n_iter_ < 100
+
+    def test_n_init(self) -> None:
+        kmeans = Kmeans(2, 100, "random", random_seed=42, n_init=5)
+        kmeans.fit(self.dataset)
+
+        assert kmeans.n_init == 5
+        assert 0 <= kmeans.best_init_ < 5
+        assert kmeans.inertia_ == pytest.approx(4.0)
+
+    @pytest.mark.parametrize("distance_metric",
+                             ["euclidean", "sqeuclidean", "manhattan", "chebyshev", "minkowski(3)", "cosine"],
+                             )
+    def test_distance_metric(self, distance_metric: str) -> None:
+        kmeans = Kmeans(2, 100, "kmeans++", distance_metric, random_seed=42)
+
+        assert repr(kmeans).endswith(f"distance_metric={distance_metric})")
+        assert len(kmeans.fit_predict(self.dataset)) == len(self.dataset)
+
+    @pytest.mark.parametrize("empty_cluster_strategy", ["farthest-point", "highest-sse"])
+    def test_empty_cluster_strategy(self, empty_cluster_strategy: str) -> None:
+        dataset = [[0.0], [0.0], [0.0], [10.0]]
+        kmeans = Kmeans(3, 100, "random", random_seed=42, empty_cluster_strategy=empty_cluster_strategy)
+        kmeans.fit(dataset)
+
+        assert len(kmeans.centroids_) == 3
+        assert len(kmeans.clusters_) == 3
+
+    def test_empty_cluster_strategy_error(self) -> None:
+        dataset = [[0.0], [0.0], [0.0], [10.0]]
+        kmeans = Kmeans(3, 100, "kmeans++", random_seed=42, empty_cluster_strategy="error")
+        with pytest.raises(ValueError, match="empty"):
+            kmeans.fit(dataset)
+
+    def test_predict_before_fit(self) -> None:
+        kmeans = Kmeans(2)
+        with pytest.raises(ValueError):
+            kmeans.predict(self.dataset)
+
+
+class TestKMeansAlgorithm:
+    """
+    Test the Elkan and Hamerly algorithms give the Lloyd labels with fewer distance computations.
+    """
+
+    dataset = [[(i * 0.37) % 10.0 + i % 3, (i * 0.11) % 5.0] for i in range(300)]
+
+    @pytest.mark.parametrize("algorithm", ["elkan", "hamerly"])
+    def test_same_labels(self, algorithm: str) -> None:
+        lloyd = Kmeans(5, 100, "kmeans++", random_seed=42)
+        bounded = Kmeans(5, 100, "kmeans++", random_seed=42, algorithm=algorithm)
+
+        assert bounded.algorithm == algorithm
+        assert bounded.fit_predict(self.dataset) == lloyd.fit_predict(self.dataset)
+        assert bounded.inertia_ == lloyd.inertia_
+        assert bounded.n_distances_ < lloyd.n_distances_
+
+    def test_invalid_algorithm(self) -> None:
+        with pytest.raises(ValueError, match="algorithm"):
+            Kmeans(2, algorithm="foo")
+        with pytest.raises(ValueError, match="triangle inequality"):
+            Kmeans(2, distance_metric="cosine", algorithm="elkan").fit(self.dataset)
+
+
+class TestKMeansInit:
+    """
+    Test the greedy k-means++ and k-means|| seedings separate well separated clusters.
+    """
+
+    dataset = [[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [10.0, 0.0], [10.0, 1.0], [10.0, 2.0]]
+
+    @pytest.mark.parametrize("centroids_init_method", ["greedy-kmeans++", "kmeans||"])
+    def test_fit(self, centroids_init_method: str) -> None:
+        kmeans = Kmeans(2, 100, centroids_init_method, random_seed=42)
+        labels = kmeans.fit_predict(self.dataset)
+
+        assert labels[:3] == [labels[0]] * 3
+        assert labels[3:] == [labels[3]] * 3
+        assert kmeans.inertia_ == pytest.approx(4.0)
+
+
+class TestKMeansInitCentroids:
+    """
+    Test warm-starting from given centroids.
+    """
+
+    dataset = [[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [10.0, 0.0], [10.0, 1.0], [10.0, 2.0]]
+
+    @pytest.mark.parametrize("dtype", [np.float32, np.float64])
+    def test_warm_start(self, dtype: type) -> None:
+        previous = Kmeans(2, 100, "kmeans++", random_seed=42)
+        previous.fit(self.dataset)
+        centroids = np.array([previous.centroids_[i] for i in range(2)])
+
+        kmeans = Kmeans(2, 100, init_centroids=centroids, n_init=3)
+        kmeans.fit(np.array(self.dataset, dtype=dtype))
+
+        assert kmeans.labels_ == previous.labels_
+        assert kmeans.n_iter_ == 1
+
+    def test_invalid_init_centroids(self) -> None:
+        with pytest.raises(ValueError, match="init_centroids"):
+            Kmeans(2, init_centroids=[[0.0, 0.0]])
+        with pytest.raises(ValueError, match="dimension"):
+            Kmeans(2, init_centroids=[[0.0], [1.0]]).fit(self.dataset)
+
+
+class TestKMeansPickle:
+    """
+    Test a fitted model survives pickling.
+    """
+
+    dataset = [[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [10.0, 0.0], [10.0, 1.0], [10.0, 2.0]]
+
+    @pytest.mark.parametrize("dtype", [np.float32, np.float64])
+    def test_round_trip(self, dtype: type) -> None:
+        kmeans = Kmeans(2, 50, "kmeans++", "manhattan", random_seed=42, algorithm="elkan")
+        kmeans.fit(np.array(self.dataset, dtype=dtype))
+
+        loaded = pickle.loads(pickle.dumps(kmeans))
+
+        assert repr(loaded) == repr(kmeans)
+        assert loaded.dtype == kmeans.dtype
+        assert loaded.algorithm == "elkan"
+        assert loaded.labels_ == kmeans.labels_
+        assert loaded.inertia_ == kmeans.inertia_
+        assert loaded.centroids_ == kmeans.centroids_
+        assert loaded.predict([[0.0, 0.0], [12.0, 3.0]]) == kmeans.predict([[0.0, 0.0], [12.0, 3.0]])
+
+    def test_unknown_dtype(self) -> None:
+        kmeans = Kmeans(2, 50, random_seed=42)
+        kmeans.fit(self.dataset)
+        _, state = kmeans.__getstate__()
+
+        with pytest.raises(ValueError, match="unsupported dtype"):
+            kmeans.__setstate__(("float16", state))
+
+
+class TestKMeansFloat32:
+    """
+    Test float32 numpy arrays are clustered in float32.
+    """
+
+    dataset = [[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [10.0, 0.0], [10.0, 1.0], [10.0, 2.0]]
+
+    @pytest.mark.parametrize("dtype", [np.float32, np.float64])
+    def test_fit_numpy(self, dtype: type) -> None:
+        kmeans = Kmeans(2, 100, "kmeans++", random_seed=42)
+        labels = kmeans.fit_predict(np.array(self.dataset, dtype=dtype))
+
+        assert kmeans.dtype == np.dtype(dtype).name
+        assert labels == Kmeans(2, 100, "kmeans++", random_seed=42).fit_predict(self.dataset)
+        assert kmeans.inertia_ == pytest.approx(4.0)
+
+    def test_predict_float32(self) -> None:
+        kmeans = Kmeans(2, 100, "kmeans++", random_seed=42)
+        labels = kmeans.fit_predict(np.array(self.dataset, dtype=np.float32))
+
+        assert kmeans.predict(self.dataset) == labels
+        assert kmeans.predict(np.array([[0.0, 1.0]], dtype=np.float64)) == [labels[0]]
+        assert len(kmeans.transform(np.array([[0.0, 1.0]], dtype=np.float32))[0]) == 2
+
+    def test_refit_float64(self) -> None:
+        kmeans = Kmeans(2, 100, "kmeans++", random_seed=42, n_init=3)
+        kmeans.fit(np.array(self.dataset, dtype=np.float32))
+        kmeans.fit(self.dataset)
+
+        assert kmeans.dtype == "float64"
+        assert kmeans.n_init == 3
+
+
+class TestKMeansSampleWeight:
+    """
+    Test weighted points count like repeated points.
+    """
+
+    dataset = [[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [10.0, 0.0], [10.0, 1.0], [10.0, 2.0]]
+    sample_weight = [2.0, 1.0, 1.0, 1.0, 1.0, 3.0]
+
+    @pytest.mark.parametrize("dtype", [np.float32, np.float64])
+    def test_fit_weighted(self, dtype: type) -> None:
+        kmeans = Kmeans(2, 100, "kmeans++", random_seed=42)
+        kmeans.fit(np.array(self.dataset, dtype=dtype), sample_weight=self.sample_weight)
+
+        centroids = sorted(list(centroid) for centroid in kmeans.centroids_.values())
+        assert centroids[0] == pytest.approx([1.0, 0.75])
+        assert centroids[1] == pytest.approx([10.0, 1.4])
+        assert kmeans.inertia_ == pytest.approx(5.95)
+
+    def test_invalid_sample_weight(self) -> None:
+        with pytest.raises(ValueError, match="sample_weight"):
+            Kmeans(2).fit(self.dataset, sample_weight=[1.0, 2.0])
+        with pytest.raises(ValueError, match="sample_weight"):
+            Kmeans(2).fit(self.dataset, sample_weight=[-1.0] * 6)
+
+
+class TestKMeansErrors:
+    """
+    Test invalid inputs raise `ValueError` instead of crashing the interpreter.
+    """
+
+    def test_unknown_centroids_init_method(self) -> None:
+        with pytest.raises(ValueError, match="unknown centroids init method"):
+            Kmeans(2, 100, "foo")
+
+    def test_unknown_distance_metric(self) -> None:
+        with pytest.raises(ValueError, match="unknown distance metric"):
+            Kmeans(2, 100, "random", "foo")
+
+    @pytest.mark.parametrize("dataset",
+                             [
+                                 [],
+                                 [[1.0, 2.0], [1.0]],
+                                 [[1.0, float("nan")], [1.0, 2.0]],
+                                 [[1.0, 2.0]],
+                             ],
+                             )
+    def test_fit_invalid_dataset(self, dataset: list[list[float]]) -> None:
+        with pytest.raises(ValueError):
+            Kmeans(2).fit(dataset)
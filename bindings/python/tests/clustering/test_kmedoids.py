@@ -0,0 +1,51 @@
+import numpy as np
+import pytest
+
+from toymlrs.clustering import Kmedoids
+
+
+def levenshtein(a: str, b: str) -> int:
+    row = list(range(len(b) + 1))
+    for i, ca in enumerate(a):
+        diagonal, row[0] = row[0], i + 1
+        for j, cb in enumerate(b):
+            diagonal, row[j + 1] = row[j + 1], min(diagonal + (ca != cb), row[j] + 1, row[j + 1] + 1)
+    return row[-1]
+
+
+class TestKmedoids:
+    """
+    Test the k-medoids algorithm.
+    """
+
+    dataset = [[1.0, 1.0], [1.5, 2.0], [3.0, 4.0], [5.0, 7.0], [3.5, 5.0], [4.5, 5.0], [3.5, 4.5]]
+
+    def test_repr(self) -> None:
+        assert repr(Kmedoids(3)) == "Kmedoids(k=3, max_iter=100, algorithm=fasterpam, distance_metric=euclidean)"
+
+    @pytest.mark.parametrize("algorithm", ["pam", "fasterpam"])
+    def test_fit_predict(self, algorithm: str) -> None:
+        kmedoids = Kmedoids(2, algorithm=algorithm, random_seed=42)
+        labels = kmedoids.fit_predict(np.array(self.dataset))
+
+        assert sorted(kmedoids.medoid_indices_) == [0, 4]
+        assert labels == [labels[0]] * 2 + [labels[2]] * 5
+        assert kmedoids.predict([[0.0, 0.0], [4.0, 6.0]]) == [labels[0], labels[2]]
+
+    def test_precomputed(self) -> None:
+        words = ["kitten", "sitting", "mitten", "bitten", "apple", "apply", "ample", "maple"]
+        distances = [[float(levenshtein(a, b)) for b in words] for a in words]
+        kmedoids = Kmedoids(2, random_seed=3)
+        kmedoids.fit_precomputed(distances)
+
+        assert kmedoids.labels_ == [kmedoids.labels_[0]] * 4 + [kmedoids.labels_[4]] * 4
+        assert kmedoids.labels_[0] != kmedoids.labels_[4]
+        assert sorted(i // 4 for i in kmedoids.medoid_indices_) == [0, 1]
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="algorithm"):
+            Kmedoids(2, algorithm="foo")
+        with pytest.raises(ValueError, match="dimension mismatch"):
+            Kmedoids(2).fit_precomputed([[0.0, 1.0, 2.0], [1.0, 0.0, 3.0]])
+        with pytest.raises(ValueError, match="not fitted"):
+            Kmedoids(2).predict(self.dataset)
@@ -0,0 +1,49 @@
+import numpy as np
+import pytest
+
+from toymlrs.clustering import MeanShift, estimate_bandwidth
+
+
+class TestMeanShift:
+    """
+    Test the mean shift algorithm.
+    """
+
+    dataset = [
+        [x + dx, y + dy]
+        for x, y in [(0.0, 0.0), (5.0, 5.0), (0.0, 5.0)]
+        for dx, dy in [(0.0, 0.0), (0.3, 0.1), (-0.2, 0.3), (0.1, -0.3), (-0.3, -0.2)]
+    ]
+
+    def test_repr(self) -> None:
+        assert repr(MeanShift()) == "MeanShift(bandwidth=None, kernel=flat, bin_seeding=False, distance_metric=euclidean)"
+
+    @pytest.mark.parametrize("kernel", ["flat", "gaussian"])
+    @pytest.mark.parametrize("bin_seeding", [False, True])
+    def test_fit_predict(self, kernel: str, bin_seeding: bool) -> None:
+        mean_shift = MeanShift(1.5, kernel=kernel, bin_seeding=bin_seeding)
+        labels = mean_shift.fit_predict(np.array(self.dataset))
+
+        assert labels == [labels[0]] * 5 + [labels[5]] * 5 + [labels[10]] * 5
+        assert len(set(labels)) == 3
+        assert sorted(mean_shift.cluster_centers_) == [0, 1, 2]
+        assert np.allclose(mean_shift.cluster_centers_[labels[5]], [4.98, 4.98], atol=0.1)
+        assert mean_shift.predict([[4.0, 6.0], [1.0, -1.0]]) == [labels[5], labels[0]]
+
+    def test_estimate_bandwidth(self) -> None:
+        assert estimate_bandwidth([[0.0], [1.0], [2.0], [3.0]], quantile=0.5) == 1.0
+        mean_shift = MeanShift()
+        mean_shift.fit(self.dataset)
+
+        assert mean_shift.bandwidth_ == pytest.approx(estimate_bandwidth(self.dataset))
+        assert len(mean_shift.cluster_centers_) == 3
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="kernel"):
+            MeanShift(kernel="foo")
+        with pytest.raises(ValueError, match="bandwidth"):
+            MeanShift(0.0).fit(self.dataset)
+        with pytest.raises(ValueError, match="quantile"):
+            estimate_bandwidth(self.dataset, quantile=1.5)
+        with pytest.raises(ValueError, match="not fitted"):
+            MeanShift().predict(self.dataset)
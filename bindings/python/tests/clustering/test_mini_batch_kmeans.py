@@ -0,0 +1,46 @@
+import numpy as np
+import pytest
+
+from toymlrs.clustering import MiniBatchKmeans
+
+
+class TestMiniBatchKmeans:
+    """
+    Test the mini-batch k-means algorithm.
+    """
+
+    dataset = [[(i % 2) * 10.0 + (i % 5) * 0.1, (i % 2) * 10.0 - (i % 3) * 0.1] for i in range(200)]
+
+    def test_repr(self) -> None:
+        sut = MiniBatchKmeans(2, 16)
+
+        assert repr(sut) == "MiniBatchKmeans(k=2, batch_size=16, max_iter=100, centroids_init_method=kmeans++, distance_metric=euclidean)"
+
+    def test_fit_predict(self) -> None:
+        kmeans = MiniBatchKmeans(2, 16, 10, random_seed=42)
+        labels = kmeans.fit_predict(self.dataset)
+
+        assert labels[0] != labels[1]
+        assert labels == [labels[i % 2] for i in range(200)]
+        assert kmeans.n_steps_ == 125
+        assert sum(kmeans.counts_) == 125 * 16
+        assert kmeans.predict(np.array([[0.0, 0.0], [10.0, 10.0]])) == labels[:2]
+        assert len(kmeans.transform([[0.0, 0.0]])[0]) == 2
+
+    def test_partial_fit(self) -> None:
+        kmeans = MiniBatchKmeans(2, random_seed=42)
+        for start in range(0, 200, 50):
+            kmeans.partial_fit(self.dataset[start:start + 50])
+
+        assert kmeans.n_steps_ == 4
+        assert sum(kmeans.counts_) == 200
+        assert len(kmeans.labels_) == 50
+        assert set(kmeans.centroids_) == {0, 1}
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="unknown centroids init method"):
+            MiniBatchKmeans(2, centroids_init_method="foo")
+        with pytest.raises(ValueError, match="batch_size"):
+            MiniBatchKmeans(2, 0).fit(self.dataset)
+        with pytest.raises(ValueError, match="not fitted"):
+            MiniBatchKmeans(2).predict(self.dataset)
@@ -0,0 +1,61 @@
+import numpy as np
+import pytest
+
+from toymlrs.clustering import Dbscan, Optics
+
+
+class TestOptics:
+    """
+    Test the OPTICS algorithm.
+    """
+
+    rng = np.random.default_rng(42)
+    # A dense blob, a sparse blob and noise
+    dataset = np.vstack([
+        rng.normal([0.0, 0.0], 0.2, size=(30, 2)),
+        rng.normal([10.0, 0.0], 0.8, size=(30, 2)),
+        [[5.0, 8.0], [-6.0, 6.0], [15.0, -9.0], [4.0, -7.0]],
+    ])
+
+    def test_repr(self) -> None:
+        assert repr(Optics(4)) == "Optics(min_samples=4, max_eps=inf, cluster_method=xi, distance_metric=euclidean)"
+
+    def test_reachability_plot(self) -> None:
+        optics = Optics(5)
+        optics.fit(self.dataset)
+
+        assert sorted(optics.ordering_) == list(range(64))
+        plot = optics.reachability_[optics.ordering_]
+        assert np.isinf(plot[0]) and np.isfinite(plot[1:]).all()
+        assert optics.core_distances_.shape == (64,)
+        assert optics.predecessor_[optics.ordering_[0]] == -1
+        assert optics.cluster_hierarchy_.shape[1] == 2
+
+    def test_fit_predict(self) -> None:
+        labels = np.array(Optics(5).fit_predict(self.dataset))
+
+        for label in set(labels) - {-1}:
+            assert len(set(np.flatnonzero(labels == label) // 30)) == 1
+        assert (labels[:30] != -1).any() and (labels[30:60] != -1).any()
+        assert (labels[60:] == -1).all()
+
+    def test_extract_dbscan(self) -> None:
+        optics = Optics(5, max_eps=5.0, cluster_method="dbscan", eps=2.0)
+        optics.fit(self.dataset)
+        dbscan = Dbscan(2.0, 5)
+        dbscan.fit(self.dataset)
+
+        assert optics.n_clusters_ == dbscan.n_clusters_ == 2
+        core = dbscan.core_sample_indices_
+        assert np.array_equal(np.array(optics.labels_)[core], np.array(dbscan.labels_)[core])
+
+        labels, hierarchy = optics.extract_xi(0.05)
+        assert len(labels) == 64 and hierarchy.shape[1] == 2
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="cluster_method"):
+            Optics(cluster_method="foo")
+        with pytest.raises(ValueError, match="eps"):
+            Optics(cluster_method="dbscan").fit(self.dataset)
+        with pytest.raises(ValueError, match="not fitted"):
+            Optics().extract_dbscan(0.5)
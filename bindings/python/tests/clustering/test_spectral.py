@@ -0,0 +1,55 @@
+import math
+
+import numpy as np
+import pytest
+
+from toymlrs.clustering import Kmeans, SpectralClustering
+
+
+class TestSpectralClustering:
+    """
+    Test the spectral clustering algorithm.
+    """
+
+    # An inner ring of radius 1 and an outer ring of radius 4 around the same center
+    dataset = [
+        [radius * math.cos(2 * math.pi * i / n), radius * math.sin(2 * math.pi * i / n)]
+        for radius, n in [(1.0, 20), (4.0, 40)]
+        for i in range(n)
+    ]
+
+    def test_repr(self) -> None:
+        assert (
+            repr(SpectralClustering(3))
+            == "SpectralClustering(n_clusters=3, affinity=rbf, gamma=1, n_neighbors=10, distance_metric=euclidean)"
+        )
+
+    @pytest.mark.parametrize("affinity", ["rbf", "nearest-neighbors"])
+    def test_fit_predict(self, affinity: str) -> None:
+        spectral = SpectralClustering(2, affinity=affinity, n_neighbors=5, random_seed=42)
+        labels = spectral.fit_predict(np.array(self.dataset))
+
+        assert labels == [labels[0]] * 20 + [labels[20]] * 40
+        assert labels[0] != labels[20]
+        assert np.allclose(np.linalg.norm(spectral.embedding_, axis=1), 1.0)
+        assert spectral.eigenvalues_[1] < 1e-3
+
+        kmeans_labels = Kmeans(2, 100, random_seed=42).fit_predict(self.dataset)
+        assert kmeans_labels != labels
+
+    def test_precomputed(self) -> None:
+        affinity = np.kron(np.eye(2), np.ones((3, 3)))
+        affinity[2, 3] = affinity[3, 2] = 0.1
+        spectral = SpectralClustering(2, random_seed=7)
+        spectral.fit_precomputed(affinity)
+
+        assert spectral.labels_ == [spectral.labels_[0]] * 3 + [spectral.labels_[3]] * 3
+        assert spectral.labels_[0] != spectral.labels_[3]
+
+    def test_errors(self) -> None:
+        with pytest.raises(ValueError, match="affinity"):
+            SpectralClustering(2, affinity="foo")
+        with pytest.raises(ValueError, match="dimension mismatch"):
+            SpectralClustering(2).fit_precomputed([[1.0, 0.5, 0.0], [0.5, 1.0, 0.0]])
+        with pytest.raises(ValueError, match="gamma"):
+            SpectralClustering(2, gamma=0.0).fit(self.dataset)
@@ -0,0 +1,135 @@
+//! Compare the contiguous `Points` layout with the previous `Vec<Point>` of `Vec<f64>` layout.
+//!
+//! Run with `cargo bench -p toymlrs-clustering`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use toymlrs_clustering::kmeans::{
+    CentroidsInitMethod, Cluster, Clusters, Distance, DistanceMetric, Kmeans, Points,
+};
+
+const DIM: usize = 16;
+const K: usize = 8;
+
+fn random_values(n_samples: usize) -> Vec<f64> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    (0..n_samples * DIM).map(|_| rng.gen::<f64>()).collect()
+}
+
+/// The previous layout: one heap allocation per point.
+struct LegacyPoints(Vec<Vec<f64>>);
+
+impl LegacyPoints {
+    fn get_clusters(&self, centroids: &HashMap<usize, Vec<f64>>) -> Clusters {
+        let mut clusters = Clusters::default();
+        for (index, point) in self.0.iter().enumerate() {
+            let nearest = centroids
+                .iter()
+                .map(|(&i, c)| (i, DistanceMetric::Euclidean.distance(c, point)))
+                .fold((0, f64::MAX), |best, c| if c.1 < best.1 { c } else { best })
+                .0;
+            clusters
+                .cluster_map
+                .entry(nearest)
+                .or_insert(Cluster::default())
+                .point_indices
+                .push(index);
+        }
+        clusters
+    }
+
+    fn get_centroids(&self, clusters: &Clusters) -> HashMap<usize, Vec<f64>> {
+        clusters
+            .cluster_map
+            .iter()
+            .map(|(&cluster_index, cluster)| {
+                // A fresh vector per fold step, as the previous `Clusters::get_centroids`
+                let sum: Vec<f64> = cluster
+                    .point_indices
+                    .iter()
+                    .map(|&i| &self.0[i])
+                    .fold(vec![0.0; DIM], |acc, p| {
+                        acc.iter().zip(p).map(|(a, b)| a + b).collect()
+                    });
+                let n = cluster.point_indices.len() as f64;
+                (cluster_index, sum.into_iter().map(|x| x / n).collect())
+            })
+            .collect()
+    }
+}
+
+fn bench_lloyd_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lloyd_step");
+    for n_samples in [1_000, 10_000, 100_000] {
+        let values = random_values(n_samples);
+        let points = Points::view(&values, DIM).unwrap();
+        let legacy = LegacyPoints(values.chunks(DIM).map(|row| row.to_vec()).collect());
+        let centroids = points
+            .get_init_centroids(
+                CentroidsInitMethod::Random,
+                K,
+                Some(42),
+                &DistanceMetric::Euclidean,
+            )
+            .unwrap();
+        let legacy_centroids: HashMap<usize, Vec<f64>> = centroids
+            .centroid_map
+            .iter()
+            .map(|(&i, c)| (i, c.values.clone()))
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("legacy", n_samples), &n_samples, |b, _| {
+            b.iter(|| {
+                let clusters = legacy.get_clusters(black_box(&legacy_centroids));
+                legacy.get_centroids(&clusters)
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("contiguous", n_samples),
+            &n_samples,
+            |b, _| {
+                b.iter(|| {
+                    let clusters =
+                        black_box(&centroids).get_clusters(&points, &DistanceMetric::Euclidean);
+                    clusters.get_centroids(&points)
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_fit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fit");
+    for n_samples in [1_000, 10_000, 100_000] {
+        let values = random_values(n_samples);
+        let nested: Vec<Vec<f64>> = values.chunks(DIM).map(|row| row.to_vec()).collect();
+        let kmeans = || {
+            let mut kmeans = Kmeans::new(
+                K,
+                10,
+                CentroidsInitMethod::Random,
+                DistanceMetric::Euclidean,
+                Some(42),
+            );
+            kmeans.tol = 0.0;
+            kmeans
+        };
+
+        group.bench_with_input(BenchmarkId::new("nested", n_samples), &n_samples, |b, _| {
+            b.iter(|| kmeans().fit(black_box(nested.clone())).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("view", n_samples), &n_samples, |b, _| {
+            b.iter(|| {
+                kmeans()
+                    .fit(Points::view(black_box(&values), DIM).unwrap())
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_lloyd_step, bench_fit);
+criterion_main!(benches);
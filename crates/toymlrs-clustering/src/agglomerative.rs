@@ -0,0 +1,472 @@
+use crate::dataset::IntoPoints;
+use crate::distance::{Distance, DistanceMetric};
+use crate::{Error, Float};
+use parse_display::{Display, FromStr};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+/// How the distance between two clusters is computed from the distances between their points.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, FromStr)]
+pub enum Linkage {
+    /// Distance between the closest points of the clusters
+    #[display("single")]
+    Single,
+    /// Distance between the farthest points of the clusters
+    #[display("complete")]
+    Complete,
+    /// Mean distance between the points of the clusters
+    #[display("average")]
+    Average,
+    /// Increase of the within-cluster variance, only meaningful with the Euclidean distance
+    #[default]
+    #[display("ward")]
+    Ward,
+}
+
+impl Linkage {
+    /// Lance-Williams update of the distance from a cluster `k` to the merge of `i` and `j`.
+    fn update<F: Float>(
+        &self,
+        d_ki: F,
+        d_kj: F,
+        d_ij: F,
+        size_k: usize,
+        size_i: usize,
+        size_j: usize,
+    ) -> F {
+        match self {
+            Linkage::Single => d_ki.min(d_kj),
+            Linkage::Complete => d_ki.max(d_kj),
+            Linkage::Average => {
+                let (size_i, size_j) = (F::from_f64(size_i as f64), F::from_f64(size_j as f64));
+                (size_i * d_ki + size_j * d_kj) / (size_i + size_j)
+            }
+            Linkage::Ward => {
+                let (size_k, size_i, size_j) = (
+                    F::from_f64(size_k as f64),
+                    F::from_f64(size_i as f64),
+                    F::from_f64(size_j as f64),
+                );
+                let squared = ((size_k + size_i) * d_ki * d_ki + (size_k + size_j) * d_kj * d_kj
+                    - size_k * d_ij * d_ij)
+                    / (size_k + size_i + size_j);
+                squared.max(F::zero()).sqrt()
+            }
+        }
+    }
+}
+
+/// A merge of two clusters, the `i`-th merge of `n` points creating the cluster `n + i`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Merge<F: Float = f64> {
+    /// The smaller of the merged cluster ids, a point index when below the number of points
+    pub left: usize,
+    pub right: usize,
+    /// Linkage distance between the merged clusters
+    pub distance: F,
+    /// Number of points of the new cluster
+    pub size: usize,
+}
+
+/// Agglomerative hierarchical clustering, repeatedly merging the two closest clusters.
+///
+/// Starting from one cluster per point, the whole merge tree is built with the nearest-neighbour
+/// chain algorithm, then cut either at `n_clusters` clusters or below `distance_threshold`,
+/// exactly one of them being set.
+#[derive(Debug)]
+pub struct AgglomerativeClustering<F: Float = f64, D: Distance<F> = DistanceMetric> {
+    /// Number of clusters to stop at
+    pub n_clusters: Option<usize>,
+    /// Linkage distance from which the clusters are no longer merged
+    pub distance_threshold: Option<F>,
+    pub linkage: Linkage,
+    pub distance_metric: D,
+    labels: Vec<usize>,
+    merges: Vec<Merge<F>>,
+    fitted_n_clusters: usize,
+}
+
+impl<F: Float> Default for AgglomerativeClustering<F> {
+    fn default() -> Self {
+        AgglomerativeClustering {
+            n_clusters: Some(2),
+            distance_threshold: None,
+            linkage: Linkage::default(),
+            distance_metric: DistanceMetric::default(),
+            labels: Vec::new(),
+            merges: Vec::new(),
+            fitted_n_clusters: 0,
+        }
+    }
+}
+
+impl<F: Float> AgglomerativeClustering<F> {
+    pub fn new(
+        n_clusters: Option<usize>,
+        distance_threshold: Option<F>,
+        linkage: Linkage,
+        distance_metric: DistanceMetric,
+    ) -> Self {
+        AgglomerativeClustering {
+            n_clusters,
+            distance_threshold,
+            linkage,
+            distance_metric,
+            ..AgglomerativeClustering::default()
+        }
+    }
+}
+
+impl<F: Float, D: Distance<F>> AgglomerativeClustering<F, D> {
+    /// Use a custom distance between the points.
+    pub fn with_distance_metric<E: Distance<F>>(
+        self,
+        distance_metric: E,
+    ) -> AgglomerativeClustering<F, E> {
+        AgglomerativeClustering {
+            n_clusters: self.n_clusters,
+            distance_threshold: self.distance_threshold,
+            linkage: self.linkage,
+            distance_metric,
+            labels: self.labels,
+            merges: self.merges,
+            fitted_n_clusters: self.fitted_n_clusters,
+        }
+    }
+
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        if self.n_clusters.is_some() == self.distance_threshold.is_some() {
+            return Err(Error::InvalidParameter {
+                name: "n_clusters".to_string(),
+                reason: "exactly one of n_clusters and distance_threshold must be set".to_string(),
+            });
+        }
+        if let Some(threshold) = self.distance_threshold {
+            if !(threshold.is_finite() && threshold >= F::zero()) {
+                return Err(Error::InvalidParameter {
+                    name: "distance_threshold".to_string(),
+                    reason: "must be non-negative and finite".to_string(),
+                });
+            }
+        }
+        let points = points.into_points()?;
+        points.check()?;
+        let n = points.len();
+        if let Some(k) = self.n_clusters {
+            if k == 0 || k > n {
+                return Err(Error::InvalidK { k, n_samples: n });
+            }
+        }
+
+        #[cfg(feature = "parallel")]
+        let rows = points.par_rows();
+        #[cfg(not(feature = "parallel"))]
+        let rows = points.rows();
+        let mut distances: Vec<Vec<F>> = rows
+            .map(|point| {
+                points
+                    .rows()
+                    .map(|other| self.distance_metric.distance(point, other))
+                    .collect()
+            })
+            .collect();
+        let mut merges = nn_chain(&mut distances, self.linkage);
+        // The linkages are monotonic, so sorting keeps every merge after those it relies on
+        merges.sort_by(|a, b| {
+            a.distance
+                .partial_cmp(&b.distance)
+                .unwrap_or(Ordering::Equal)
+        });
+        self.merges = relabel(n, merges);
+
+        let n_merges = match (self.n_clusters, self.distance_threshold) {
+            (Some(k), _) => n - k,
+            (_, Some(threshold)) => self
+                .merges
+                .iter()
+                .take_while(|merge| merge.distance < threshold)
+                .count(),
+            _ => 0,
+        };
+        self.fitted_n_clusters = n - n_merges;
+        let mut parents: Vec<usize> = (0..2 * n - 1).collect();
+        for (i, merge) in self.merges[..n_merges].iter().enumerate() {
+            parents[merge.left] = n + i;
+            parents[merge.right] = n + i;
+        }
+        // Clusters are numbered in the order of their first point
+        let mut cluster_labels = vec![None; 2 * n - 1];
+        let mut n_labels = 0;
+        self.labels = (0..n)
+            .map(|point| {
+                let mut root = point;
+                while parents[root] != root {
+                    root = parents[root];
+                }
+                *cluster_labels[root].get_or_insert_with(|| {
+                    n_labels += 1;
+                    n_labels - 1
+                })
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// The cluster of each point of the last fit.
+    pub fn get_labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// Number of clusters of the last fit, useful when stopping at a distance threshold.
+    pub fn get_n_clusters(&self) -> usize {
+        self.fitted_n_clusters
+    }
+
+    /// Every merge of the last fit, by increasing distance, down to a single cluster.
+    pub fn get_merges(&self) -> &[Merge<F>] {
+        &self.merges
+    }
+
+    /// The merges of the last fit as a SciPy linkage matrix, one
+    /// `[left, right, distance, size]` row per merge.
+    pub fn get_linkage_matrix(&self) -> Vec<[F; 4]> {
+        self.merges
+            .iter()
+            .map(|merge| {
+                [
+                    F::from_f64(merge.left as f64),
+                    F::from_f64(merge.right as f64),
+                    merge.distance,
+                    F::from_f64(merge.size as f64),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Nearest-neighbour chain, following nearest neighbours until two clusters are each other's,
+/// then merging them.
+///
+/// Merges are returned in the order found, each cluster being known by the largest index of
+/// its points, and `distances` is updated in place.
+fn nn_chain<F: Float>(distances: &mut [Vec<F>], linkage: Linkage) -> Vec<Merge<F>> {
+    let n = distances.len();
+    let mut sizes = vec![1; n];
+    let mut merges = Vec::with_capacity(n.saturating_sub(1));
+    let mut chain: Vec<usize> = Vec::with_capacity(n);
+    for _ in 1..n {
+        if chain.is_empty() {
+            chain.extend(sizes.iter().position(|&size| size > 0));
+        }
+        let (x, y, distance) = loop {
+            let x = chain[chain.len() - 1];
+            let previous = chain.len().checked_sub(2).map(|i| chain[i]);
+            let mut nearest = previous;
+            let mut nearest_distance = previous.map_or(F::infinity(), |y| distances[x][y]);
+            for i in (0..n).filter(|&i| i != x && sizes[i] > 0) {
+                if distances[x][i] < nearest_distance {
+                    nearest = Some(i);
+                    nearest_distance = distances[x][i];
+                }
+            }
+            // There is always another active cluster
+            let y = nearest.unwrap_or_default();
+            if Some(y) == previous {
+                chain.truncate(chain.len() - 2);
+                break (x.min(y), x.max(y), nearest_distance);
+            }
+            chain.push(y);
+        };
+        let (size_x, size_y) = (sizes[x], sizes[y]);
+        merges.push(Merge {
+            left: x,
+            right: y,
+            distance,
+            size: size_x + size_y,
+        });
+        sizes[x] = 0;
+        sizes[y] = size_x + size_y;
+        for k in (0..n).filter(|&k| k != y && sizes[k] > 0) {
+            let updated = linkage.update(
+                distances[k][x],
+                distances[k][y],
+                distance,
+                sizes[k],
+                size_x,
+                size_y,
+            );
+            distances[k][y] = updated;
+            distances[y][k] = updated;
+        }
+    }
+    merges
+}
+
+/// Give the merges sorted by distance the SciPy cluster ids, `n + i` for the `i`-th merge.
+fn relabel<F: Float>(n: usize, merges: Vec<Merge<F>>) -> Vec<Merge<F>> {
+    // Each point index stands for its current cluster, pointing to its id
+    let mut ids: Vec<usize> = (0..n).collect();
+    merges
+        .into_iter()
+        .enumerate()
+        .map(|(i, merge)| {
+            let (a, b) = (ids[merge.left], ids[merge.right]);
+            ids[merge.left] = n + i;
+            ids[merge.right] = n + i;
+            Merge {
+                left: a.min(b),
+                right: a.max(b),
+                ..merge
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::Points;
+
+    fn create_test_points() -> Vec<Vec<f64>> {
+        vec![vec![0.0], vec![1.0], vec![3.0], vec![7.0], vec![8.0]]
+    }
+
+    #[test]
+    fn test_agglomerative_linkage_matrix() {
+        // Same as scipy.cluster.hierarchy.linkage
+        for (linkage, last_distances) in [
+            (Linkage::Single, [2.0, 4.0]),
+            (Linkage::Complete, [3.0, 8.0]),
+            (Linkage::Average, [2.5, 37.0 / 6.0]),
+            (
+                Linkage::Ward,
+                [(25.0_f64 / 3.0).sqrt(), (2.4_f64).sqrt() * 37.0 / 6.0],
+            ),
+        ] {
+            let mut agglomerative =
+                AgglomerativeClustering::new(Some(1), None, linkage, DistanceMetric::Euclidean);
+            agglomerative.fit(create_test_points()).unwrap();
+            let matrix = agglomerative.get_linkage_matrix();
+            assert_eq!(matrix[..2], [[0.0, 1.0, 1.0, 2.0], [3.0, 4.0, 1.0, 2.0]]);
+            assert_eq!(matrix[2][..2], [2.0, 5.0]);
+            assert_eq!(matrix[3][..2], [6.0, 7.0]);
+            assert!(
+                (matrix[2][2] - last_distances[0]).abs() < 1e-12,
+                "{linkage}"
+            );
+            assert!(
+                (matrix[3][2] - last_distances[1]).abs() < 1e-12,
+                "{linkage}"
+            );
+            assert_eq!([matrix[2][3], matrix[3][3]], [3.0, 5.0]);
+            assert_eq!(agglomerative.get_labels(), [0; 5]);
+        }
+    }
+
+    #[test]
+    fn test_agglomerative_cut() {
+        let mut agglomerative = AgglomerativeClustering::new(
+            Some(3),
+            None,
+            Linkage::Complete,
+            DistanceMetric::Euclidean,
+        );
+        agglomerative.fit(create_test_points()).unwrap();
+        assert_eq!(agglomerative.get_labels(), [0, 0, 1, 2, 2]);
+        assert_eq!(agglomerative.get_n_clusters(), 3);
+        // The whole tree is kept whatever the cut
+        assert_eq!(agglomerative.get_merges().len(), 4);
+
+        let mut agglomerative = AgglomerativeClustering::new(
+            None,
+            Some(2.5),
+            Linkage::Single,
+            DistanceMetric::Euclidean,
+        );
+        agglomerative.fit(create_test_points()).unwrap();
+        assert_eq!(agglomerative.get_labels(), [0, 0, 0, 1, 1]);
+        assert_eq!(agglomerative.get_n_clusters(), 2);
+        agglomerative.distance_threshold = Some(0.5);
+        agglomerative.fit(create_test_points()).unwrap();
+        assert_eq!(agglomerative.get_labels(), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_agglomerative_chain() {
+        // Single linkage follows the chain that complete linkage breaks
+        let points: Vec<Vec<f64>> = (0..10)
+            .map(|i| vec![i as f64, 0.0])
+            .chain([vec![4.5, 3.0], vec![4.5, 4.0]])
+            .collect();
+        let mut agglomerative =
+            AgglomerativeClustering::new(Some(2), None, Linkage::Single, DistanceMetric::Euclidean);
+        agglomerative.fit(points.clone()).unwrap();
+        assert_eq!(
+            agglomerative.get_labels(),
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1]
+        );
+        agglomerative.linkage = Linkage::Complete;
+        agglomerative.fit(points).unwrap();
+        assert_ne!(agglomerative.get_labels()[0], agglomerative.get_labels()[9]);
+    }
+
+    #[test]
+    fn test_agglomerative_metric_and_f32() {
+        let values: Vec<f32> = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 9.0, 9.0, 9.0, 8.0];
+        let mut agglomerative = AgglomerativeClustering::new(
+            Some(2),
+            None,
+            Linkage::Average,
+            DistanceMetric::Manhattan,
+        );
+        agglomerative
+            .fit(Points::view(&values, 2).unwrap())
+            .unwrap();
+        assert_eq!(agglomerative.get_labels(), [0, 0, 0, 1, 1]);
+        assert_eq!(agglomerative.get_merges()[0].distance, 1.0_f32);
+    }
+
+    #[test]
+    fn test_agglomerative_errors() {
+        let mut agglomerative = AgglomerativeClustering::new(
+            Some(2),
+            Some(1.0),
+            Linkage::Ward,
+            DistanceMetric::Euclidean,
+        );
+        assert!(matches!(
+            agglomerative.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        let mut agglomerative = AgglomerativeClustering::new(
+            None,
+            Some(-1.0),
+            Linkage::Ward,
+            DistanceMetric::Euclidean,
+        );
+        assert!(matches!(
+            agglomerative.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        let mut agglomerative = AgglomerativeClustering::new(
+            Some(2),
+            None,
+            Linkage::Single,
+            DistanceMetric::Minkowski(0.0),
+        );
+        assert!(matches!(
+            agglomerative.fit(create_test_points()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        let mut agglomerative =
+            AgglomerativeClustering::new(Some(6), None, Linkage::Ward, DistanceMetric::Euclidean);
+        assert_eq!(
+            agglomerative.fit(create_test_points()),
+            Err(Error::InvalidK { k: 6, n_samples: 5 })
+        );
+        assert_eq!(agglomerative.fit(vec![]), Err(Error::EmptyDataset));
+        assert_eq!("average".parse(), Ok(Linkage::Average));
+    }
+}
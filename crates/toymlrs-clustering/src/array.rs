@@ -0,0 +1,100 @@
+//! Conversions between `ndarray` arrays and the clustering types, enabled by the `ndarray`
+//! feature.
+
+use crate::dataset::{IntoPoints, Points};
+use crate::kmeans::{Centroids, Labels};
+use crate::{Error, Float};
+use ndarray::{Array1, Array2, ArrayView2};
+
+impl<'a, F: Float> IntoPoints<'a, F> for ArrayView2<'a, F> {
+    /// Borrow the array when it is in standard (row-major, contiguous) layout, copy it otherwise.
+    fn into_points(self) -> Result<Points<'a, F>, Error> {
+        let dim = self.ncols();
+        match self.to_slice() {
+            Some(values) => Points::view(values, dim),
+            None => Points::new(self.iter().copied().collect(), dim),
+        }
+    }
+}
+
+impl<'a, F: Float> IntoPoints<'a, F> for &'a Array2<F> {
+    fn into_points(self) -> Result<Points<'a, F>, Error> {
+        self.view().into_points()
+    }
+}
+
+impl Labels {
+    /// The label of each sample as an array.
+    pub fn to_array(&self) -> Array1<usize> {
+        Array1::from_vec(self.0.clone())
+    }
+}
+
+impl<F: Float> Centroids<F> {
+    /// The centroids as a `(k, dim)` array, the row `i` being the centroid of cluster `i`.
+    pub fn to_array(&self) -> Array2<F> {
+        let mut cluster_indices: Vec<&usize> = self.centroid_map.keys().collect();
+        cluster_indices.sort();
+        let values: Vec<F> = cluster_indices
+            .into_iter()
+            .flat_map(|index| self.centroid_map[index].values.iter().copied())
+            .collect();
+        Array2::from_shape_vec((self.centroid_map.len(), self.dim().unwrap_or(0)), values)
+            .expect("centroids have the same dimension")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kmeans::{CentroidsInitMethod, DistanceMetric, Kmeans};
+    use ndarray::array;
+
+    #[test]
+    fn test_array_into_points() {
+        let values = array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]];
+        let points = values.view().into_points().unwrap();
+        assert_eq!(points.as_slice().as_ptr(), values.as_ptr());
+        assert_eq!(points.dim(), 2);
+
+        // Column-major arrays are copied row by row
+        let transposed = values.t();
+        let points = transposed.into_points().unwrap();
+        assert_eq!(points.as_slice(), &[1.0, 3.0, 5.0, 2.0, 4.0, 6.0]);
+        assert_eq!(points.dim(), 3);
+
+        assert_eq!(
+            Array2::<f64>::zeros((0, 2)).view().into_points(),
+            Err(Error::EmptyDataset)
+        );
+    }
+
+    #[test]
+    fn test_kmeans_fit_array() {
+        let values = array![
+            [1.0, 0.0],
+            [1.0, 1.0],
+            [1.0, 2.0],
+            [10.0, 0.0],
+            [10.0, 1.0],
+            [10.0, 2.0]
+        ];
+        let mut kmeans = Kmeans::new(
+            2,
+            100,
+            CentroidsInitMethod::KmeansPlusPlus,
+            DistanceMetric::Euclidean,
+            Some(42),
+        );
+        let labels = kmeans.fit_predict(values.view()).unwrap().to_array();
+        assert_eq!(labels.len(), 6);
+        assert_eq!(labels[0], labels[2]);
+        assert_ne!(labels[0], labels[3]);
+        assert_eq!(kmeans.predict(&values).unwrap().to_array(), labels);
+
+        let centroids = kmeans.get_centroids().to_array();
+        assert_eq!(centroids.dim(), (2, 2));
+        assert_eq!(centroids.row(labels[0]).to_vec(), vec![1.0, 1.0]);
+        assert_eq!(centroids.row(labels[3]).to_vec(), vec![10.0, 1.0]);
+    }
+}
@@ -0,0 +1,494 @@
+//! Distance bounds used by the Elkan and Hamerly k-means assignment steps.
+//!
+//! Both keep an upper bound on the distance from each point to its centroid and lower bounds on
+//! the distances to the other centroids, moved by the centroid shifts after each update. A
+//! centroid is only skipped when the bounds prove it is strictly farther than the assigned one,
+//! so the labels are the ones of the Lloyd assignment, ties included.
+
+use crate::dataset::Points;
+use crate::distance::Distance;
+use crate::kmeans::{Centroids, KmeansAlgorithm};
+use crate::Float;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub(crate) struct Bounds<F: Float> {
+    algorithm: KmeansAlgorithm,
+    k: usize,
+    /// Upper bound on the distance from each point to its centroid
+    upper: Vec<F>,
+    /// Elkan: lower bound on the distance from each point to each centroid, row by row.
+    /// Hamerly: lower bound on the distance from each point to its second closest centroid.
+    lower: Vec<F>,
+    initialized: bool,
+}
+
+/// The centroids ordered by index, with half the distance between each pair of centroids.
+struct CentroidDistances<'a, F: Float> {
+    centroids: Vec<&'a [F]>,
+    /// Half the distance between centroids `a` and `c` at `a * k + c`
+    half_distances: Vec<F>,
+    /// Half the distance from each centroid to its closest other centroid
+    half_min_distances: Vec<F>,
+}
+
+impl<'a, F: Float> CentroidDistances<'a, F> {
+    fn new(centroids: &'a Centroids<F>, distance_metric: &impl Distance<F>) -> Self {
+        let k = centroids.centroid_map.len();
+        let centroids: Vec<&[F]> = (0..k)
+            .map(|index| centroids.centroid_map[&index].values.as_slice())
+            .collect();
+        let half = F::from_f64(0.5);
+        let mut half_distances = vec![F::zero(); k * k];
+        for a in 0..k {
+            for c in a + 1..k {
+                let d = half * distance_metric.distance(centroids[a], centroids[c]);
+                half_distances[a * k + c] = d;
+                half_distances[c * k + a] = d;
+            }
+        }
+        let half_min_distances = (0..k)
+            .map(|a| {
+                (0..k)
+                    .filter(|&c| c != a)
+                    .map(|c| half_distances[a * k + c])
+                    .fold(F::infinity(), F::min)
+            })
+            .collect();
+        CentroidDistances {
+            centroids,
+            half_distances,
+            half_min_distances,
+        }
+    }
+
+    fn n_distances(&self) -> usize {
+        let k = self.centroids.len();
+        k * k.saturating_sub(1) / 2
+    }
+}
+
+impl<F: Float> Bounds<F> {
+    pub(crate) fn new(algorithm: KmeansAlgorithm, n_points: usize, k: usize) -> Self {
+        let lower_width = match algorithm {
+            KmeansAlgorithm::Elkan => k,
+            _ => 1,
+        };
+        Bounds {
+            algorithm,
+            k,
+            upper: vec![F::infinity(); n_points],
+            lower: vec![F::zero(); n_points * lower_width],
+            initialized: false,
+        }
+    }
+
+    /// Assign each point to its nearest centroid, starting from the current `labels`.
+    ///
+    /// Returns the number of distances computed.
+    pub(crate) fn assign(
+        &mut self,
+        points: &Points<F>,
+        centroids: &Centroids<F>,
+        distance_metric: &impl Distance<F>,
+        labels: &mut [usize],
+    ) -> usize {
+        let centroid_distances = CentroidDistances::new(centroids, distance_metric);
+        let initialized = self.initialized;
+        let algorithm = self.algorithm;
+        let lower_width = self.lower.len() / self.upper.len().max(1);
+        #[cfg(feature = "parallel")]
+        let points_iter = labels
+            .par_iter_mut()
+            .zip(self.upper.par_iter_mut())
+            .zip(self.lower.par_chunks_mut(lower_width.max(1)))
+            .zip(points.par_rows());
+        #[cfg(not(feature = "parallel"))]
+        let points_iter = labels
+            .iter_mut()
+            .zip(self.upper.iter_mut())
+            .zip(self.lower.chunks_mut(lower_width.max(1)))
+            .zip(points.rows());
+        let n_distances: usize = points_iter
+            .map(|(((label, upper), lower), point)| {
+                let bounds = PointBounds {
+                    label,
+                    upper,
+                    lower,
+                };
+                match (initialized, algorithm) {
+                    (false, _) => bounds.init(point, &centroid_distances, distance_metric),
+                    (true, KmeansAlgorithm::Elkan) => {
+                        bounds.elkan(point, &centroid_distances, distance_metric)
+                    }
+                    (true, _) => bounds.hamerly(point, &centroid_distances, distance_metric),
+                }
+            })
+            .sum();
+        self.initialized = true;
+        n_distances + centroid_distances.n_distances()
+    }
+
+    /// Forget the bounds of a point moved to another cluster, e.g. to fill an empty cluster.
+    pub(crate) fn invalidate(&mut self, point_index: usize) {
+        self.upper[point_index] = F::infinity();
+        if self.algorithm != KmeansAlgorithm::Elkan {
+            // The lower bound was on the centroids other than the previous one
+            self.lower[point_index] = F::zero();
+        }
+    }
+
+    /// Move the bounds by how much each centroid moved.
+    ///
+    /// Returns the number of distances computed.
+    pub(crate) fn update(
+        &mut self,
+        previous: &Centroids<F>,
+        centroids: &Centroids<F>,
+        distance_metric: &impl Distance<F>,
+        labels: &[usize],
+    ) -> usize {
+        let shifts: Vec<F> = (0..self.k)
+            .map(|index| {
+                distance_metric.distance(
+                    &previous.centroid_map[&index].values,
+                    &centroids.centroid_map[&index].values,
+                )
+            })
+            .collect();
+        for (upper, &label) in self.upper.iter_mut().zip(labels) {
+            *upper += shifts[label];
+        }
+        match self.algorithm {
+            KmeansAlgorithm::Elkan => {
+                for lower in self.lower.chunks_mut(self.k) {
+                    for (bound, &shift) in lower.iter_mut().zip(&shifts) {
+                        *bound = (*bound - shift).max(F::zero());
+                    }
+                }
+            }
+            _ => {
+                // The lower bound is on any centroid but the assigned one, so it moves by the
+                // largest shift among the others
+                let largest =
+                    shifts
+                        .iter()
+                        .enumerate()
+                        .fold((0, F::zero()), |best, (index, &shift)| {
+                            if shift > best.1 {
+                                (index, shift)
+                            } else {
+                                best
+                            }
+                        });
+                let second_largest = shifts
+                    .iter()
+                    .enumerate()
+                    .filter(|&(index, _)| index != largest.0)
+                    .fold(F::zero(), |best, (_, &shift)| best.max(shift));
+                for (bound, &label) in self.lower.iter_mut().zip(labels) {
+                    let shift = if label == largest.0 {
+                        second_largest
+                    } else {
+                        largest.1
+                    };
+                    *bound = (*bound - shift).max(F::zero());
+                }
+            }
+        }
+        self.k
+    }
+}
+
+/// The bounds of a single point.
+struct PointBounds<'a, F: Float> {
+    label: &'a mut usize,
+    upper: &'a mut F,
+    lower: &'a mut [F],
+}
+
+impl<F: Float> PointBounds<'_, F> {
+    /// Compute the distances to every centroid.
+    fn init(
+        self,
+        point: &[F],
+        centroid_distances: &CentroidDistances<F>,
+        distance_metric: &impl Distance<F>,
+    ) -> usize {
+        let distances: Vec<F> = centroid_distances
+            .centroids
+            .iter()
+            .map(|centroid| distance_metric.distance(centroid, point))
+            .collect();
+        self.set_nearest(&distances);
+        distances.len()
+    }
+
+    /// Set the nearest centroid, breaking ties with the smallest index like the Lloyd assignment.
+    fn set_nearest(self, distances: &[F]) {
+        let (nearest, distance) =
+            distances
+                .iter()
+                .enumerate()
+                .fold((0, F::infinity()), |best, (index, &distance)| {
+                    if distance < best.1 {
+                        (index, distance)
+                    } else {
+                        best
+                    }
+                });
+        *self.label = nearest;
+        *self.upper = distance;
+        if self.lower.len() == distances.len() {
+            self.lower.copy_from_slice(distances);
+        } else {
+            self.lower[0] = distances
+                .iter()
+                .enumerate()
+                .filter(|&(index, _)| index != nearest)
+                .fold(F::infinity(), |best, (_, &distance)| best.min(distance));
+        }
+    }
+
+    fn elkan(
+        self,
+        point: &[F],
+        centroid_distances: &CentroidDistances<F>,
+        distance_metric: &impl Distance<F>,
+    ) -> usize {
+        let k = centroid_distances.centroids.len();
+        let mut label = *self.label;
+        if *self.upper < centroid_distances.half_min_distances[label] {
+            return 0;
+        }
+        let mut n_distances = 0;
+        let mut tight = false;
+        for c in 0..k {
+            // Whether the bounds prove centroid `c` is strictly farther than the assigned one
+            let skip = |upper: F, lower: &[F], label: usize| {
+                upper < lower[c] || upper < centroid_distances.half_distances[label * k + c]
+            };
+            if c == label || skip(*self.upper, self.lower, label) {
+                continue;
+            }
+            if !tight {
+                *self.upper = distance_metric.distance(centroid_distances.centroids[label], point);
+                self.lower[label] = *self.upper;
+                n_distances += 1;
+                tight = true;
+                if skip(*self.upper, self.lower, label) {
+                    continue;
+                }
+            }
+            let distance = distance_metric.distance(centroid_distances.centroids[c], point);
+            self.lower[c] = distance;
+            n_distances += 1;
+            if distance < *self.upper || (distance == *self.upper && c < label) {
+                label = c;
+                *self.upper = distance;
+            }
+        }
+        *self.label = label;
+        n_distances
+    }
+
+    fn hamerly(
+        self,
+        point: &[F],
+        centroid_distances: &CentroidDistances<F>,
+        distance_metric: &impl Distance<F>,
+    ) -> usize {
+        let label = *self.label;
+        let bound = centroid_distances.half_min_distances[label].max(self.lower[0]);
+        if *self.upper < bound {
+            return 0;
+        }
+        *self.upper = distance_metric.distance(centroid_distances.centroids[label], point);
+        if *self.upper < bound {
+            return 1;
+        }
+        let upper = *self.upper;
+        let distances: Vec<F> = centroid_distances
+            .centroids
+            .iter()
+            .enumerate()
+            .map(|(c, centroid)| {
+                if c == label {
+                    upper
+                } else {
+                    distance_metric.distance(centroid, point)
+                }
+            })
+            .collect();
+        self.set_nearest(&distances);
+        distances.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::Point;
+    use crate::distance::DistanceMetric;
+    use std::collections::HashMap;
+
+    fn create_centroids(values: &[[f64; 2]]) -> Centroids {
+        Centroids {
+            centroid_map: values
+                .iter()
+                .enumerate()
+                .map(|(index, values)| {
+                    (
+                        index,
+                        Point {
+                            values: values.to_vec(),
+                        },
+                    )
+                })
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    fn create_test_points() -> Points<'static> {
+        Points::try_from(vec![vec![1.0, 0.0], vec![9.0, 0.0], vec![21.0, 0.0]]).unwrap()
+    }
+
+    /// Check the bounds hold for the actual distances to the centroids.
+    fn assert_bounds_hold(
+        bounds: &Bounds<f64>,
+        points: &Points,
+        centroids: &Centroids,
+        labels: &[usize],
+    ) {
+        let metric = DistanceMetric::Euclidean;
+        for ((i, point), &label) in points.rows().enumerate().zip(labels) {
+            let distances = centroids.get_distances(point, &metric);
+            assert!(bounds.upper[i] >= distances[label]);
+            match bounds.algorithm {
+                KmeansAlgorithm::Elkan => {
+                    for (c, &distance) in distances.iter().enumerate() {
+                        assert!(bounds.lower[i * bounds.k + c] <= distance);
+                    }
+                }
+                _ => {
+                    for (c, &distance) in distances.iter().enumerate() {
+                        if c != label {
+                            assert!(bounds.lower[i] <= distance);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bounds_assign_init() {
+        let points = create_test_points();
+        let centroids = create_centroids(&[[0.0, 0.0], [10.0, 0.0], [20.0, 0.0]]);
+        let metric = DistanceMetric::Euclidean;
+
+        let mut elkan = Bounds::new(KmeansAlgorithm::Elkan, 3, 3);
+        let mut labels = vec![0; 3];
+        // Every point to every centroid, and each pair of centroids
+        assert_eq!(
+            elkan.assign(&points, &centroids, &metric, &mut labels),
+            9 + 3
+        );
+        assert_eq!(labels, vec![0, 1, 2]);
+        assert_eq!(elkan.upper, vec![1.0, 1.0, 1.0]);
+        assert_eq!(
+            elkan.lower,
+            vec![1.0, 9.0, 19.0, 9.0, 1.0, 11.0, 21.0, 11.0, 1.0]
+        );
+
+        let mut hamerly = Bounds::new(KmeansAlgorithm::Hamerly, 3, 3);
+        let mut labels = vec![0; 3];
+        assert_eq!(
+            hamerly.assign(&points, &centroids, &metric, &mut labels),
+            9 + 3
+        );
+        assert_eq!(labels, vec![0, 1, 2]);
+        assert_eq!(hamerly.upper, vec![1.0, 1.0, 1.0]);
+        // The distance to the second closest centroid
+        assert_eq!(hamerly.lower, vec![9.0, 9.0, 11.0]);
+    }
+
+    #[test]
+    fn test_bounds_assign_skips_far_centroids() {
+        let points = create_test_points();
+        let centroids = create_centroids(&[[0.0, 0.0], [10.0, 0.0], [20.0, 0.0]]);
+        let metric = DistanceMetric::Euclidean;
+        for algorithm in [KmeansAlgorithm::Elkan, KmeansAlgorithm::Hamerly] {
+            let mut bounds = Bounds::new(algorithm, 3, 3);
+            let mut labels = vec![0; 3];
+            bounds.assign(&points, &centroids, &metric, &mut labels);
+            // Each point is within half the distance to the closest other centroid, only the
+            // distances between the centroids are computed
+            assert_eq!(bounds.assign(&points, &centroids, &metric, &mut labels), 3);
+            assert_eq!(labels, vec![0, 1, 2]);
+        }
+    }
+
+    #[test]
+    fn test_bounds_update_elkan() {
+        let points = create_test_points();
+        let previous = create_centroids(&[[0.0, 0.0], [10.0, 0.0], [20.0, 0.0]]);
+        let metric = DistanceMetric::Euclidean;
+        let mut bounds = Bounds::new(KmeansAlgorithm::Elkan, 3, 3);
+        let mut labels = vec![0; 3];
+        bounds.assign(&points, &previous, &metric, &mut labels);
+
+        // Shifts of 3, 4 and 0
+        let centroids = create_centroids(&[[3.0, 0.0], [10.0, 4.0], [20.0, 0.0]]);
+        assert_eq!(bounds.update(&previous, &centroids, &metric, &labels), 3);
+        // The upper bound grows by the shift of the assigned centroid
+        assert_eq!(bounds.upper, vec![4.0, 5.0, 1.0]);
+        // Each lower bound shrinks by the shift of its centroid, down to zero
+        assert_eq!(
+            bounds.lower,
+            vec![0.0, 5.0, 19.0, 6.0, 0.0, 11.0, 18.0, 7.0, 1.0]
+        );
+        assert_bounds_hold(&bounds, &points, &centroids, &labels);
+    }
+
+    #[test]
+    fn test_bounds_update_hamerly() {
+        let points = create_test_points();
+        let previous = create_centroids(&[[0.0, 0.0], [10.0, 0.0], [20.0, 0.0]]);
+        let metric = DistanceMetric::Euclidean;
+        let mut bounds = Bounds::new(KmeansAlgorithm::Hamerly, 3, 3);
+        let mut labels = vec![0; 3];
+        bounds.assign(&points, &previous, &metric, &mut labels);
+
+        // Shifts of 5, 2 and 1, the largest one being the centroid of the first point
+        let centroids = create_centroids(&[[0.0, 5.0], [10.0, 2.0], [21.0, 0.0]]);
+        assert_eq!(bounds.update(&previous, &centroids, &metric, &labels), 3);
+        assert_eq!(bounds.upper, vec![6.0, 3.0, 2.0]);
+        // The first point is bounded by the other centroids, so by the second largest shift,
+        // the others by the largest shift
+        assert_eq!(bounds.lower, vec![7.0, 4.0, 6.0]);
+        assert_bounds_hold(&bounds, &points, &centroids, &labels);
+    }
+
+    #[test]
+    fn test_bounds_invalidate() {
+        let points = create_test_points();
+        let centroids = create_centroids(&[[0.0, 0.0], [10.0, 0.0], [20.0, 0.0]]);
+        let metric = DistanceMetric::Euclidean;
+        let mut bounds = Bounds::new(KmeansAlgorithm::Hamerly, 3, 3);
+        let mut labels = vec![0; 3];
+        bounds.assign(&points, &centroids, &metric, &mut labels);
+
+        // Move the first point to the last cluster, its bounds no longer hold
+        labels[0] = 2;
+        bounds.invalidate(0);
+        assert_eq!(bounds.upper[0], f64::INFINITY);
+        assert_eq!(bounds.lower[0], 0.0);
+        // The point is reassigned to its nearest centroid
+        assert_eq!(
+            bounds.assign(&points, &centroids, &metric, &mut labels),
+            3 + 3
+        );
+        assert_eq!(labels, vec![0, 1, 2]);
+    }
+}
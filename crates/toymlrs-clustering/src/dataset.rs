@@ -0,0 +1,370 @@
+use crate::distance::{Distance, DistanceMetric};
+use crate::{Error, Float};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::borrow::Cow;
+
+/// A single point, used for the centroids and the cluster centers.
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point<F: Float = f64> {
+    pub values: Vec<F>,
+}
+
+impl<F: Float> Point<F> {
+    pub fn dim(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Distance between two points, an error if they have different dimensions.
+    pub fn distance(&self, other: &Point<F>, metric: Option<DistanceMetric>) -> Result<F, Error> {
+        if self.dim() != other.dim() {
+            return Err(Error::DimensionMismatch {
+                expected: self.dim(),
+                found: other.dim(),
+            });
+        }
+        Ok(metric
+            .unwrap_or_default()
+            .distance(&self.values, &other.values))
+    }
+}
+
+/// Points stored row by row in one contiguous buffer.
+///
+/// The buffer is either owned, or borrowed from the caller with [`Points::view`] to cluster
+/// without copying the data.
+///
+/// The points can be given a weight each with [`Points::with_sample_weight`], e.g. the number of
+/// times each point appears in a deduplicated dataset.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Points<'a, F: Float = f64> {
+    values: Cow<'a, [F]>,
+    dim: usize,
+    sample_weight: Option<Cow<'a, [F]>>,
+}
+
+impl<F: Float> Points<'static, F> {
+    /// Own the row-major `values` of points with `dim` features.
+    pub fn new(values: Vec<F>, dim: usize) -> Result<Self, Error> {
+        Self::check_shape(values.len(), dim)?;
+        Ok(Points {
+            values: Cow::Owned(values),
+            dim,
+            sample_weight: None,
+        })
+    }
+}
+
+impl<'a, F: Float> Points<'a, F> {
+    /// Borrow the row-major `values` of points with `dim` features.
+    pub fn view(values: &'a [F], dim: usize) -> Result<Self, Error> {
+        Self::check_shape(values.len(), dim)?;
+        Ok(Points {
+            values: Cow::Borrowed(values),
+            dim,
+            sample_weight: None,
+        })
+    }
+
+    fn check_shape(len: usize, dim: usize) -> Result<(), Error> {
+        if dim == 0 || len == 0 {
+            return Err(Error::EmptyDataset);
+        }
+        if !len.is_multiple_of(dim) {
+            return Err(Error::RaggedRows {
+                row: len / dim,
+                expected: dim,
+                found: len % dim,
+            });
+        }
+        Ok(())
+    }
+
+    /// Weight each point, the weights must be finite, non-negative and not all zero.
+    pub fn with_sample_weight(self, sample_weight: impl Into<Cow<'a, [F]>>) -> Result<Self, Error> {
+        let sample_weight = sample_weight.into();
+        let invalid = |reason: String| Error::InvalidParameter {
+            name: "sample_weight".to_string(),
+            reason,
+        };
+        if sample_weight.len() != self.len() {
+            return Err(invalid(format!(
+                "expected {} weights, one per point, got {}",
+                self.len(),
+                sample_weight.len()
+            )));
+        }
+        if let Some(index) = sample_weight
+            .iter()
+            .position(|w| !w.is_finite() || *w < F::zero())
+        {
+            return Err(invalid(format!(
+                "weights must be finite and non-negative, got {:?} at index {}",
+                sample_weight[index], index
+            )));
+        }
+        if sample_weight.iter().all(|w| w.is_zero()) {
+            return Err(invalid("weights must not all be zero".to_string()));
+        }
+        Ok(Points {
+            sample_weight: Some(sample_weight),
+            ..self
+        })
+    }
+
+    /// A borrowed view of the points.
+    pub fn as_view(&self) -> Points<'_, F> {
+        Points {
+            values: Cow::Borrowed(&self.values),
+            dim: self.dim,
+            sample_weight: self.sample_weight.as_deref().map(Cow::Borrowed),
+        }
+    }
+
+    /// Number of points.
+    pub fn len(&self) -> usize {
+        self.values.len().checked_div(self.dim).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Number of features of each point.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// The row-major values of all the points.
+    pub fn as_slice(&self) -> &[F] {
+        &self.values
+    }
+
+    /// The weight of each point, `None` when all the points weigh the same.
+    pub fn sample_weight(&self) -> Option<&[F]> {
+        self.sample_weight.as_deref()
+    }
+
+    /// The weight of the `index`-th point, `1` when the points are not weighted.
+    pub fn weight(&self, index: usize) -> F {
+        self.sample_weight
+            .as_ref()
+            .map_or(F::one(), |sample_weight| sample_weight[index])
+    }
+
+    /// The values of the `index`-th point.
+    pub fn row(&self, index: usize) -> &[F] {
+        &self.values[index * self.dim..(index + 1) * self.dim]
+    }
+
+    /// Iterate over the values of each point.
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, F> {
+        self.values.chunks_exact(self.dim.max(1))
+    }
+
+    /// Iterate in parallel over the values of each point.
+    #[cfg(feature = "parallel")]
+    pub fn par_rows(&self) -> rayon::slice::ChunksExact<'_, F> {
+        self.values.par_chunks_exact(self.dim.max(1))
+    }
+
+    /// Check the points are non-empty and only have finite values.
+    ///
+    /// Returns the dimension of the points.
+    pub fn check(&self) -> Result<usize, Error> {
+        if self.is_empty() {
+            return Err(Error::EmptyDataset);
+        }
+        if let Some(index) = self.values.iter().position(|x| !x.is_finite()) {
+            return Err(Error::NonFiniteValue {
+                row: index / self.dim,
+                column: index % self.dim,
+            });
+        }
+        Ok(self.dim)
+    }
+}
+
+impl<F: Float> TryFrom<Vec<Vec<F>>> for Points<'static, F> {
+    type Error = Error;
+
+    fn try_from(point_values: Vec<Vec<F>>) -> Result<Self, Error> {
+        Points::try_from(point_values.as_slice())
+    }
+}
+
+impl<F: Float> TryFrom<&[Vec<F>]> for Points<'static, F> {
+    type Error = Error;
+
+    fn try_from(point_values: &[Vec<F>]) -> Result<Self, Error> {
+        let dim = point_values.first().ok_or(Error::EmptyDataset)?.len();
+        let mut values = Vec::with_capacity(point_values.len() * dim);
+        for (row, point) in point_values.iter().enumerate() {
+            if point.len() != dim {
+                return Err(Error::RaggedRows {
+                    row,
+                    expected: dim,
+                    found: point.len(),
+                });
+            }
+            values.extend_from_slice(point);
+        }
+        Points::new(values, dim)
+    }
+}
+
+/// Conversion into the [`Points`] accepted by the clustering algorithms.
+///
+/// Nested vectors are copied into a contiguous buffer, [`Points`] and references to them are
+/// used as they are.
+pub trait IntoPoints<'a, F: Float = f64> {
+    fn into_points(self) -> Result<Points<'a, F>, Error>;
+}
+
+impl<'a, F: Float> IntoPoints<'a, F> for Points<'a, F> {
+    fn into_points(self) -> Result<Points<'a, F>, Error> {
+        Ok(self)
+    }
+}
+
+impl<'a, F: Float> IntoPoints<'a, F> for &'a Points<'_, F> {
+    fn into_points(self) -> Result<Points<'a, F>, Error> {
+        Ok(self.as_view())
+    }
+}
+
+impl<F: Float> IntoPoints<'static, F> for Vec<Vec<F>> {
+    fn into_points(self) -> Result<Points<'static, F>, Error> {
+        Points::try_from(self)
+    }
+}
+
+impl<F: Float> IntoPoints<'static, F> for &[Vec<F>] {
+    fn into_points(self) -> Result<Points<'static, F>, Error> {
+        Points::try_from(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_distance() {
+        let p1 = Point {
+            values: vec![1.0, 2.0, 3.0],
+        };
+        let p2 = Point {
+            values: vec![4.0, 5.0, 6.0],
+        };
+        assert_eq!(
+            p1.distance(&p2, Some(DistanceMetric::Euclidean)),
+            Ok(5.196152422706632)
+        );
+    }
+
+    #[test]
+    fn test_point_distance_different_dimensions() {
+        let p1 = Point {
+            values: vec![1.0, 2.0],
+        };
+        let p2 = Point {
+            values: vec![4.0, 5.0, 6.0],
+        };
+        assert_eq!(
+            p1.distance(&p2, Some(DistanceMetric::Euclidean)),
+            Err(Error::DimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_points_layout() {
+        let points =
+            Points::try_from(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]).unwrap();
+        assert_eq!(points.len(), 3);
+        assert_eq!(points.dim(), 2);
+        assert_eq!(points.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(points.row(1), &[3.0, 4.0]);
+        assert_eq!(
+            points.rows().collect::<Vec<_>>(),
+            vec![&[1.0, 2.0], &[3.0, 4.0], &[5.0, 6.0]]
+        );
+    }
+
+    #[test]
+    fn test_points_view_borrows() {
+        let values = vec![1.0, 2.0, 3.0, 4.0];
+        let points = Points::view(&values, 2).unwrap();
+        assert_eq!(points.as_slice().as_ptr(), values.as_ptr());
+        assert_eq!(
+            (&points).into_points().unwrap().as_slice().as_ptr(),
+            values.as_ptr()
+        );
+        assert_eq!(points, Points::new(values.clone(), 2).unwrap());
+    }
+
+    #[test]
+    fn test_points_shape_errors() {
+        assert_eq!(Points::<f64>::new(vec![], 2), Err(Error::EmptyDataset));
+        assert_eq!(Points::new(vec![1.0], 0), Err(Error::EmptyDataset));
+        assert_eq!(
+            Points::new(vec![1.0, 2.0, 3.0], 2),
+            Err(Error::RaggedRows {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Points::try_from(vec![vec![1.0, 2.0], vec![1.0]]),
+            Err(Error::RaggedRows {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(Points::<f64>::try_from(vec![]), Err(Error::EmptyDataset));
+    }
+
+    #[test]
+    fn test_points_sample_weight() {
+        let points = Points::new(vec![1.0, 2.0, 3.0, 4.0], 2).unwrap();
+        assert_eq!(points.sample_weight(), None);
+        assert_eq!(points.weight(1), 1.0);
+        let weights = vec![3.0, 0.0];
+        let points = points.with_sample_weight(weights.as_slice()).unwrap();
+        assert_eq!(points.sample_weight(), Some(&[3.0, 0.0][..]));
+        assert_eq!(points.weight(0), 3.0);
+        assert_eq!(points.as_view().weight(1), 0.0);
+
+        let points = Points::new(vec![1.0, 2.0, 3.0, 4.0], 2).unwrap();
+        for sample_weight in [
+            vec![1.0],
+            vec![1.0, -1.0],
+            vec![1.0, f64::NAN],
+            vec![0.0, 0.0],
+        ] {
+            assert!(matches!(
+                points.clone().with_sample_weight(sample_weight),
+                Err(Error::InvalidParameter { name, .. }) if name == "sample_weight"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_points_check() {
+        let points = Points::new(vec![1.0, 2.0, 3.0, 4.0], 2).unwrap();
+        assert_eq!(points.check(), Ok(2));
+        assert_eq!(Points::<f64>::default().check(), Err(Error::EmptyDataset));
+        assert_eq!(
+            Points::new(vec![1.0, 2.0, 1.0, f64::NAN], 2)
+                .unwrap()
+                .check(),
+            Err(Error::NonFiniteValue { row: 1, column: 1 })
+        );
+    }
+}
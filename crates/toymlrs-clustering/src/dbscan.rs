@@ -0,0 +1,222 @@
+use crate::dataset::IntoPoints;
+use crate::distance::{Distance, DistanceMetric};
+use crate::neighbors::radius_neighbors;
+use crate::{Error, Float};
+use std::collections::VecDeque;
+
+/// DBSCAN, grouping the points lying in dense regions and flagging the others as noise.
+///
+/// A point with at least `min_samples` points within `eps`, itself included, is a core point.
+/// Clusters are the core points reachable from each other through their neighbourhoods, with
+/// the points in the neighbourhood of a core point. A point near several clusters joins the
+/// first one found, scanning the points in order.
+#[derive(Debug)]
+pub struct Dbscan<F: Float = f64, D: Distance<F> = DistanceMetric> {
+    /// Largest distance between two points for them to be neighbours
+    pub eps: F,
+    /// Number of neighbours, the point itself included, making a point a core point
+    pub min_samples: usize,
+    pub distance_metric: D,
+    labels: Vec<Option<usize>>,
+    core_sample_indices: Vec<usize>,
+    n_clusters: usize,
+}
+
+impl<F: Float> Default for Dbscan<F> {
+    fn default() -> Self {
+        Dbscan {
+            eps: F::from_f64(0.5),
+            min_samples: 5,
+            distance_metric: DistanceMetric::default(),
+            labels: Vec::new(),
+            core_sample_indices: Vec::new(),
+            n_clusters: 0,
+        }
+    }
+}
+
+impl<F: Float> Dbscan<F> {
+    pub fn new(eps: F, min_samples: usize, distance_metric: DistanceMetric) -> Self {
+        Dbscan {
+            eps,
+            min_samples,
+            distance_metric,
+            ..Dbscan::default()
+        }
+    }
+}
+
+impl<F: Float, D: Distance<F>> Dbscan<F, D> {
+    /// Use a custom distance to find the neighbours.
+    pub fn with_distance_metric<E: Distance<F>>(self, distance_metric: E) -> Dbscan<F, E> {
+        Dbscan {
+            eps: self.eps,
+            min_samples: self.min_samples,
+            distance_metric,
+            labels: self.labels,
+            core_sample_indices: self.core_sample_indices,
+            n_clusters: self.n_clusters,
+        }
+    }
+
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        if !(self.eps.is_finite() && self.eps > F::zero()) {
+            return Err(Error::InvalidParameter {
+                name: "eps".to_string(),
+                reason: "must be positive and finite".to_string(),
+            });
+        }
+        if self.min_samples == 0 {
+            return Err(Error::InvalidParameter {
+                name: "min_samples".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        let points = points.into_points()?;
+        points.check()?;
+        let neighbors = radius_neighbors(&points, self.eps, &self.distance_metric);
+        let is_core: Vec<bool> = neighbors
+            .iter()
+            .map(|neighbors| neighbors.len() >= self.min_samples)
+            .collect();
+        self.core_sample_indices = (0..points.len()).filter(|&i| is_core[i]).collect();
+        self.labels = vec![None; points.len()];
+        self.n_clusters = 0;
+        let mut queue = VecDeque::new();
+        for &start in &self.core_sample_indices {
+            if self.labels[start].is_some() {
+                continue;
+            }
+            let label = self.n_clusters;
+            self.n_clusters += 1;
+            self.labels[start] = Some(label);
+            queue.push_back(start);
+            // Only core points spread the cluster, border points join it without expanding it
+            while let Some(i) = queue.pop_front() {
+                for &j in &neighbors[i] {
+                    if self.labels[j].is_none() {
+                        self.labels[j] = Some(label);
+                        if is_core[j] {
+                            queue.push_back(j);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The cluster of each point of the last fit, `None` for noise.
+    pub fn get_labels(&self) -> &[Option<usize>] {
+        &self.labels
+    }
+
+    /// Indices of the core points of the last fit, in increasing order.
+    pub fn get_core_sample_indices(&self) -> &[usize] {
+        &self.core_sample_indices
+    }
+
+    pub fn get_n_clusters(&self) -> usize {
+        self.n_clusters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::Points;
+
+    fn create_test_points() -> Vec<Vec<f64>> {
+        vec![
+            vec![1.0, 1.0],
+            vec![1.2, 1.0],
+            vec![1.0, 1.2],
+            vec![1.1, 1.1],
+            vec![5.0, 5.0],
+            vec![5.2, 5.0],
+            vec![5.0, 5.2],
+            // Border point of the second cluster
+            vec![5.0, 5.6],
+            // Noise
+            vec![9.0, 1.0],
+        ]
+    }
+
+    #[test]
+    fn test_dbscan_fit() {
+        let mut dbscan = Dbscan::new(0.5, 3, DistanceMetric::Euclidean);
+        dbscan.fit(create_test_points()).unwrap();
+        assert_eq!(dbscan.get_n_clusters(), 2);
+        assert_eq!(
+            dbscan.get_labels(),
+            [
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                None
+            ]
+        );
+        assert_eq!(dbscan.get_core_sample_indices(), [0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_dbscan_non_convex_clusters() {
+        // Two concentric rings, which no centroid-based clustering separates
+        let points: Vec<Vec<f64>> = (0..120)
+            .map(|i| {
+                let radius = if i % 2 == 0 { 1.0 } else { 4.0 };
+                let angle = i as f64 * std::f64::consts::PI / 30.0;
+                vec![radius * angle.cos(), radius * angle.sin()]
+            })
+            .collect();
+        let mut dbscan = Dbscan::new(0.9, 3, DistanceMetric::Euclidean);
+        dbscan.fit(points).unwrap();
+        assert_eq!(dbscan.get_n_clusters(), 2);
+        let labels = dbscan.get_labels();
+        assert!(labels.iter().all(Option::is_some));
+        assert!((0..120).all(|i| labels[i] == labels[i % 2]));
+        assert_ne!(labels[0], labels[1]);
+    }
+
+    #[test]
+    fn test_dbscan_metric_and_f32() {
+        let points: Vec<Vec<f32>> = create_test_points()
+            .into_iter()
+            .map(|point| point.into_iter().map(|x| x as f32).collect())
+            .collect();
+        let values: Vec<f32> = points.concat();
+        let mut dbscan = Dbscan::new(0.5_f32, 3, DistanceMetric::Manhattan);
+        dbscan.fit(Points::view(&values, 2).unwrap()).unwrap();
+        assert_eq!(dbscan.get_n_clusters(), 2);
+        assert_eq!(dbscan.get_labels()[7], Some(1));
+        assert_eq!(dbscan.get_labels()[8], None);
+        assert_eq!(dbscan.get_core_sample_indices(), [0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_dbscan_errors() {
+        let mut dbscan = Dbscan::new(0.0, 3, DistanceMetric::Euclidean);
+        assert!(matches!(
+            dbscan.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        let mut dbscan = Dbscan::new(0.5, 0, DistanceMetric::Euclidean);
+        assert!(matches!(
+            dbscan.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        let mut dbscan = Dbscan::new(0.5, 3, DistanceMetric::Minkowski(0.0));
+        assert!(matches!(
+            dbscan.fit(create_test_points()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        let mut dbscan = Dbscan::<f64>::default();
+        assert_eq!(dbscan.fit(vec![]), Err(Error::EmptyDataset));
+    }
+}
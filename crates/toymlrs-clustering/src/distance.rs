@@ -0,0 +1,165 @@
+use crate::{Error, Float};
+use parse_display::{Display, FromStr};
+
+/// A distance between two points with the same dimension.
+///
+/// Implement this trait to cluster with a custom metric.
+pub trait Distance<F: Float = f64>: Send + Sync {
+    fn distance(&self, a: &[F], b: &[F]) -> F;
+
+    /// Whether `distance(a, c) <= distance(a, b) + distance(b, c)` always holds.
+    ///
+    /// The Elkan and Hamerly k-means algorithms rely on it to skip distance computations.
+    fn satisfies_triangle_inequality(&self) -> bool {
+        false
+    }
+
+    /// Validate the parameters of the distance, called before fitting.
+    fn check(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Display, FromStr)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DistanceMetric {
+    #[default]
+    #[display("euclidean")]
+    Euclidean,
+    #[display("sqeuclidean")]
+    SquaredEuclidean,
+    #[display("manhattan")]
+    Manhattan,
+    #[display("chebyshev")]
+    Chebyshev,
+    #[display("minkowski({0})")]
+    Minkowski(f64),
+    /// One minus the cosine similarity, zero vectors are orthogonal to every vector
+    #[display("cosine")]
+    Cosine,
+}
+
+impl<F: Float> Distance<F> for DistanceMetric {
+    fn distance(&self, a: &[F], b: &[F]) -> F {
+        let diffs = a.iter().zip(b.iter()).map(|(&x, &y)| (x - y).abs());
+        match self {
+            DistanceMetric::Euclidean => diffs.map(|d| d.powi(2)).sum::<F>().sqrt(),
+            DistanceMetric::SquaredEuclidean => diffs.map(|d| d.powi(2)).sum(),
+            DistanceMetric::Manhattan => diffs.sum(),
+            DistanceMetric::Chebyshev => diffs.fold(F::zero(), F::max),
+            DistanceMetric::Minkowski(p) => {
+                let p = F::from_f64(*p);
+                diffs.map(|d| d.powf(p)).sum::<F>().powf(p.recip())
+            }
+            DistanceMetric::Cosine => {
+                let dot: F = a.iter().zip(b.iter()).map(|(&x, &y)| x * y).sum();
+                let norm_a = a.iter().map(|&x| x * x).sum::<F>().sqrt();
+                let norm_b = b.iter().map(|&x| x * x).sum::<F>().sqrt();
+                if norm_a.is_zero() || norm_b.is_zero() {
+                    F::one()
+                } else {
+                    F::one() - dot / (norm_a * norm_b)
+                }
+            }
+        }
+    }
+
+    fn satisfies_triangle_inequality(&self) -> bool {
+        match self {
+            DistanceMetric::Euclidean | DistanceMetric::Manhattan | DistanceMetric::Chebyshev => {
+                true
+            }
+            DistanceMetric::Minkowski(p) => *p >= 1.0,
+            DistanceMetric::SquaredEuclidean | DistanceMetric::Cosine => false,
+        }
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        match self {
+            DistanceMetric::Minkowski(p) if !(p.is_finite() && *p > 0.0) => {
+                Err(Error::InvalidParameter {
+                    name: "p".to_string(),
+                    reason: format!("must be positive and finite, got {p}"),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_metrics() {
+        let a = [1.0_f64, 2.0, 3.0];
+        let b = [4.0, 6.0, 3.0];
+        assert_eq!(DistanceMetric::Euclidean.distance(&a, &b), 5.0);
+        assert_eq!(DistanceMetric::SquaredEuclidean.distance(&a, &b), 25.0);
+        assert_eq!(DistanceMetric::Manhattan.distance(&a, &b), 7.0);
+        assert_eq!(DistanceMetric::Chebyshev.distance(&a, &b), 4.0);
+        assert_eq!(DistanceMetric::Minkowski(1.0).distance(&a, &b), 7.0);
+        assert!((DistanceMetric::Minkowski(2.0).distance(&a, &b) - 5.0).abs() < 1e-12);
+        assert!(DistanceMetric::Cosine.distance(&a, &[2.0, 4.0, 6.0]).abs() < 1e-12);
+        assert_eq!(
+            DistanceMetric::Cosine.distance(&[1.0, 0.0], &[0.0, 1.0]),
+            1.0
+        );
+        assert_eq!(
+            DistanceMetric::Cosine.distance(&[0.0, 0.0], &[0.0, 1.0]),
+            1.0
+        );
+        let a = [1.0_f32, 2.0, 3.0];
+        let b = [4.0, 6.0, 3.0];
+        assert_eq!(DistanceMetric::Euclidean.distance(&a, &b), 5.0_f32);
+        assert_eq!(DistanceMetric::Minkowski(1.0).distance(&a, &b), 7.0_f32);
+    }
+
+    #[test]
+    fn test_distance_metric_triangle_inequality() {
+        let satisfies =
+            |metric: DistanceMetric| Distance::<f64>::satisfies_triangle_inequality(&metric);
+        assert!(satisfies(DistanceMetric::Euclidean));
+        assert!(satisfies(DistanceMetric::Minkowski(1.0)));
+        assert!(!satisfies(DistanceMetric::Minkowski(0.5)));
+        assert!(!satisfies(DistanceMetric::SquaredEuclidean));
+        assert!(!satisfies(DistanceMetric::Cosine));
+    }
+
+    #[test]
+    fn test_distance_metric_check() {
+        let check = |metric: DistanceMetric| Distance::<f64>::check(&metric);
+        assert_eq!(check(DistanceMetric::Minkowski(0.5)), Ok(()));
+        assert_eq!(check(DistanceMetric::Euclidean), Ok(()));
+        for p in [0.0, -2.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                check(DistanceMetric::Minkowski(p)),
+                Err(Error::InvalidParameter { name, .. }) if name == "p"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_distance_metric_parse() {
+        for metric in [
+            DistanceMetric::Euclidean,
+            DistanceMetric::SquaredEuclidean,
+            DistanceMetric::Manhattan,
+            DistanceMetric::Chebyshev,
+            DistanceMetric::Minkowski(3.0),
+            DistanceMetric::Minkowski(1.5),
+            DistanceMetric::Cosine,
+        ] {
+            assert_eq!(
+                metric.to_string().parse::<DistanceMetric>().unwrap(),
+                metric
+            );
+        }
+        assert_eq!(
+            "minkowski(3)".parse::<DistanceMetric>().unwrap(),
+            DistanceMetric::Minkowski(3.0)
+        );
+        assert!("hamming".parse::<DistanceMetric>().is_err());
+    }
+}
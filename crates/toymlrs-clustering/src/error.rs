@@ -0,0 +1,40 @@
+use parse_display::Display;
+
+/// Errors returned by the clustering algorithms.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum Error {
+    #[display("the dataset is empty")]
+    EmptyDataset,
+    #[display("ragged rows: row {row} has {found} features, expected {expected}")]
+    RaggedRows {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[display("non-finite value (NaN or infinity) at row {row}, column {column}")]
+    NonFiniteValue { row: usize, column: usize },
+    #[display("k must be between 1 and the number of samples ({n_samples}), got {k}")]
+    InvalidK { k: usize, n_samples: usize },
+    #[display("invalid parameter `{name}`: {reason}")]
+    InvalidParameter { name: String, reason: String },
+    #[display("unknown distance metric: {0:?}")]
+    UnknownDistanceMetric(String),
+    #[display("unknown centroids init method: {0:?}")]
+    UnknownCentroidsInitMethod(String),
+    #[display("cluster {cluster} became empty during fitting")]
+    EmptyCluster { cluster: usize },
+    #[display(
+        "component {component} has an ill-defined covariance, increase reg_covar or decrease the number of components"
+    )]
+    IllDefinedCovariance { component: usize },
+    #[display("the model is not fitted yet, call `fit` first")]
+    NotFitted,
+    #[display("dimension mismatch: expected {expected} features, got {found}")]
+    DimensionMismatch { expected: usize, found: usize },
+    #[display("could not save or load the model: {0}")]
+    Serialization(String),
+    #[display("unsupported model format version {found}, expected {expected}")]
+    UnsupportedFormatVersion { expected: u32, found: u32 },
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,45 @@
+use rand::distributions::uniform::SampleUniform;
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{AddAssign, DivAssign};
+
+/// The floating point types the points can be made of, `f32` or `f64`.
+pub trait Float:
+    num_traits::Float
+    + Default
+    + Debug
+    + Sum
+    + AddAssign
+    + for<'a> AddAssign<&'a Self>
+    + DivAssign
+    + SampleUniform
+    + Send
+    + Sync
+    + 'static
+{
+    /// Convert a `f64`, e.g. a hyperparameter, to the nearest value.
+    fn from_f64(value: f64) -> Self;
+
+    /// Convert to a `f64` without loss, e.g. to report a value to the bindings.
+    fn into_f64(self) -> f64;
+}
+
+impl Float for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn into_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl Float for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn into_f64(self) -> f64 {
+        self
+    }
+}
@@ -0,0 +1,754 @@
+use crate::dataset::{IntoPoints, Points};
+use crate::distance::DistanceMetric;
+use crate::kmeans::CentroidsInitMethod;
+use crate::{Error, Float};
+use parse_display::{Display, FromStr};
+use rand::random;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The shape of the covariance matrices of the components.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, FromStr)]
+pub enum CovarianceType {
+    /// Each component has its own general covariance matrix
+    #[default]
+    #[display("full")]
+    Full,
+    /// Each component has its own diagonal covariance matrix
+    #[display("diag")]
+    Diag,
+    /// Each component has its own single variance
+    #[display("spherical")]
+    Spherical,
+    /// All the components share the same general covariance matrix
+    #[display("tied")]
+    Tied,
+}
+
+/// Gaussian mixture model, fitted with the expectation-maximization algorithm.
+///
+/// Each point is softly assigned to the components, with the probability of having been drawn
+/// from each of them. The means start from centroids seeded by `init_method`, each point being
+/// first given to its nearest centroid to estimate the weights and covariances. The iterations
+/// stop once the mean log-likelihood improves by less than `tol`.
+#[derive(Debug)]
+pub struct GaussianMixture<F: Float = f64> {
+    pub n_components: usize,
+    pub covariance_type: CovarianceType,
+    /// Stop iterating once the mean log-likelihood improves by less than `tol`
+    pub tol: F,
+    /// Added to the diagonal of the covariances so they stay positive definite
+    pub reg_covar: F,
+    pub max_iter: usize,
+    /// Number of runs with different seeds, the run with the highest log-likelihood is kept
+    pub n_init: usize,
+    pub init_method: CentroidsInitMethod,
+    pub random_seed: Option<u64>,
+    weights: Vec<F>,
+    means: Vec<Vec<F>>,
+    covariances: Vec<Vec<F>>,
+    /// Lower Cholesky factors of the full and tied covariances, the variances otherwise
+    cholesky: Vec<Vec<F>>,
+    labels: Vec<usize>,
+    converged: bool,
+    n_iter: usize,
+    lower_bound: F,
+}
+
+impl<F: Float> Default for GaussianMixture<F> {
+    fn default() -> Self {
+        GaussianMixture {
+            n_components: 1,
+            covariance_type: CovarianceType::default(),
+            tol: F::from_f64(1e-3),
+            reg_covar: F::from_f64(1e-6),
+            max_iter: 100,
+            n_init: 1,
+            init_method: CentroidsInitMethod::KmeansPlusPlus,
+            random_seed: None,
+            weights: Vec::new(),
+            means: Vec::new(),
+            covariances: Vec::new(),
+            cholesky: Vec::new(),
+            labels: Vec::new(),
+            converged: false,
+            n_iter: 0,
+            lower_bound: F::neg_infinity(),
+        }
+    }
+}
+
+/// The fitted state of the best run among the `n_init` runs.
+struct BestRun<F: Float> {
+    weights: Vec<F>,
+    means: Vec<Vec<F>>,
+    covariances: Vec<Vec<F>>,
+    cholesky: Vec<Vec<F>>,
+    converged: bool,
+    n_iter: usize,
+    lower_bound: F,
+}
+
+impl<F: Float> GaussianMixture<F> {
+    pub fn new(
+        n_components: usize,
+        covariance_type: CovarianceType,
+        max_iter: usize,
+        init_method: CentroidsInitMethod,
+        random_seed: Option<u64>,
+    ) -> Self {
+        GaussianMixture {
+            n_components,
+            covariance_type,
+            max_iter,
+            init_method,
+            random_seed,
+            ..GaussianMixture::default()
+        }
+    }
+
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        if self.n_init == 0 {
+            return Err(Error::InvalidParameter {
+                name: "n_init".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        if !(self.reg_covar.is_finite() && self.reg_covar >= F::zero()) {
+            return Err(Error::InvalidParameter {
+                name: "reg_covar".to_string(),
+                reason: "must be non-negative and finite".to_string(),
+            });
+        }
+        let points = points.into_points()?;
+        let dim = points.check()?;
+        if self.n_components == 0 || self.n_components > points.len() {
+            return Err(Error::InvalidK {
+                k: self.n_components,
+                n_samples: points.len(),
+            });
+        }
+        // The seed of each run is derived from the random seed, so the whole fit is reproducible
+        let base_seed = self.random_seed.unwrap_or(random::<u64>());
+        let mut best: Option<BestRun<F>> = None;
+        for init in 0..self.n_init {
+            let seed = base_seed.wrapping_add(init as u64);
+            let centroids = points.get_init_centroids(
+                self.init_method,
+                self.n_components,
+                Some(seed),
+                &DistanceMetric::Euclidean,
+            )?;
+            let mut responsibilities = vec![vec![F::zero(); self.n_components]; points.len()];
+            for (point, responsibility) in points.rows().zip(&mut responsibilities) {
+                responsibility
+                    [centroids.get_nearest_cluster_index(point, &DistanceMetric::Euclidean)] =
+                    F::one();
+            }
+            self.m_step(&points, dim, &responsibilities)?;
+            self.converged = false;
+            self.n_iter = 0;
+            self.lower_bound = F::neg_infinity();
+            for _ in 0..self.max_iter {
+                self.n_iter += 1;
+                let previous = self.lower_bound;
+                let (log_likelihood, responsibilities) = self.e_step(&points);
+                self.lower_bound = log_likelihood;
+                self.m_step(&points, dim, &responsibilities)?;
+                if (self.lower_bound - previous).abs() < self.tol {
+                    self.converged = true;
+                    break;
+                }
+            }
+            if best
+                .as_ref()
+                .is_none_or(|best| self.lower_bound > best.lower_bound)
+            {
+                best = Some(BestRun {
+                    weights: std::mem::take(&mut self.weights),
+                    means: std::mem::take(&mut self.means),
+                    covariances: std::mem::take(&mut self.covariances),
+                    cholesky: std::mem::take(&mut self.cholesky),
+                    converged: self.converged,
+                    n_iter: self.n_iter,
+                    lower_bound: self.lower_bound,
+                });
+            }
+        }
+        if let Some(best) = best {
+            self.weights = best.weights;
+            self.means = best.means;
+            self.covariances = best.covariances;
+            self.cholesky = best.cholesky;
+            self.converged = best.converged;
+            self.n_iter = best.n_iter;
+            self.lower_bound = best.lower_bound;
+        }
+        // A last E-step so the labels match the fitted parameters
+        self.labels = self
+            .weighted_log_probabilities(&points)
+            .iter()
+            .map(|log_probabilities| argmax(log_probabilities))
+            .collect();
+        Ok(())
+    }
+
+    pub fn fit_predict<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<&[usize], Error> {
+        self.fit(points)?;
+        Ok(&self.labels)
+    }
+
+    /// The most probable component of each point.
+    pub fn predict<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Vec<usize>, Error> {
+        let points = self.check_points(points)?;
+        Ok(self
+            .weighted_log_probabilities(&points)
+            .iter()
+            .map(|log_probabilities| argmax(log_probabilities))
+            .collect())
+    }
+
+    /// Probability of each component for each point, every row summing to 1.
+    pub fn predict_proba<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Vec<Vec<F>>, Error> {
+        let points = self.check_points(points)?;
+        Ok(self.e_step(&points).1)
+    }
+
+    /// Log-likelihood of each point under the model.
+    pub fn score_samples<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Vec<F>, Error> {
+        let points = self.check_points(points)?;
+        Ok(self
+            .weighted_log_probabilities(&points)
+            .iter()
+            .map(|log_probabilities| log_sum_exp(log_probabilities))
+            .collect())
+    }
+
+    /// Mean log-likelihood of the points under the model.
+    pub fn score<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<F, Error> {
+        let scores = self.score_samples(points)?;
+        Ok(scores.iter().copied().sum::<F>() / F::from_f64(scores.len() as f64))
+    }
+
+    /// Bayesian information criterion of the model on the points, the lower the better.
+    pub fn bic<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<F, Error> {
+        let scores = self.score_samples(points)?;
+        let n = F::from_f64(scores.len() as f64);
+        Ok(F::from_f64(-2.0) * scores.into_iter().sum::<F>()
+            + F::from_f64(self.n_parameters() as f64) * n.ln())
+    }
+
+    /// Akaike information criterion of the model on the points, the lower the better.
+    pub fn aic<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<F, Error> {
+        let scores = self.score_samples(points)?;
+        Ok(F::from_f64(-2.0) * scores.into_iter().sum::<F>()
+            + F::from_f64(2.0 * self.n_parameters() as f64))
+    }
+
+    /// Number of free parameters of the model: weights, means and covariances.
+    pub fn n_parameters(&self) -> usize {
+        let k = self.means.len();
+        let dim = self.means.first().map_or(0, Vec::len);
+        let covariance_parameters = match self.covariance_type {
+            CovarianceType::Full => k * dim * (dim + 1) / 2,
+            CovarianceType::Diag => k * dim,
+            CovarianceType::Spherical => k,
+            CovarianceType::Tied => dim * (dim + 1) / 2,
+        };
+        (k * dim + covariance_parameters + k).saturating_sub(1)
+    }
+
+    fn check_points<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Points<'a, F>, Error> {
+        let expected = self.means.first().ok_or(Error::NotFitted)?.len();
+        let points = points.into_points()?;
+        let found = points.check()?;
+        if found != expected {
+            return Err(Error::DimensionMismatch { expected, found });
+        }
+        Ok(points)
+    }
+
+    /// Log of the weight times the density of each component, for each point.
+    fn weighted_log_probabilities(&self, points: &Points<F>) -> Vec<Vec<F>> {
+        let log_2_pi = F::from_f64((2.0 * std::f64::consts::PI).ln());
+        let dim = F::from_f64(points.dim() as f64);
+        #[cfg(feature = "parallel")]
+        let rows = points.par_rows();
+        #[cfg(not(feature = "parallel"))]
+        let rows = points.rows();
+        rows.map(|point| {
+            (0..self.means.len())
+                .map(|k| {
+                    let (log_det, mahalanobis) = self.log_det_and_mahalanobis(point, k);
+                    self.weights[k].ln()
+                        - F::from_f64(0.5) * (dim * log_2_pi + log_det + mahalanobis)
+                })
+                .collect()
+        })
+        .collect()
+    }
+
+    /// Log-determinant of the covariance of component `k`, and squared Mahalanobis distance
+    /// from `point` to its mean.
+    fn log_det_and_mahalanobis(&self, point: &[F], k: usize) -> (F, F) {
+        let mean = &self.means[k];
+        match self.covariance_type {
+            CovarianceType::Full | CovarianceType::Tied => {
+                let cholesky = match self.covariance_type {
+                    CovarianceType::Tied => &self.cholesky[0],
+                    _ => &self.cholesky[k],
+                };
+                let dim = mean.len();
+                // Forward substitution of L y = x - mean, |y|² being the Mahalanobis distance
+                let mut y = vec![F::zero(); dim];
+                let mut log_det = F::zero();
+                let mut mahalanobis = F::zero();
+                for i in 0..dim {
+                    let mut value = point[i] - mean[i];
+                    for j in 0..i {
+                        value = value - cholesky[i * dim + j] * y[j];
+                    }
+                    y[i] = value / cholesky[i * dim + i];
+                    mahalanobis += y[i] * y[i];
+                    log_det += F::from_f64(2.0) * cholesky[i * dim + i].ln();
+                }
+                (log_det, mahalanobis)
+            }
+            CovarianceType::Diag | CovarianceType::Spherical => {
+                let variances = &self.cholesky[k];
+                let variance = |i: usize| variances[i.min(variances.len() - 1)];
+                (0..mean.len()).fold((F::zero(), F::zero()), |(log_det, mahalanobis), i| {
+                    let diff = point[i] - mean[i];
+                    (
+                        log_det + variance(i).ln(),
+                        mahalanobis + diff * diff / variance(i),
+                    )
+                })
+            }
+        }
+    }
+
+    /// Mean log-likelihood of the points and the responsibilities of the components.
+    fn e_step(&self, points: &Points<F>) -> (F, Vec<Vec<F>>) {
+        let mut log_likelihood = F::zero();
+        let responsibilities = self
+            .weighted_log_probabilities(points)
+            .into_iter()
+            .map(|log_probabilities| {
+                let norm = log_sum_exp(&log_probabilities);
+                log_likelihood += norm;
+                log_probabilities
+                    .into_iter()
+                    .map(|log_probability| (log_probability - norm).exp())
+                    .collect()
+            })
+            .collect();
+        (
+            log_likelihood / F::from_f64(points.len() as f64),
+            responsibilities,
+        )
+    }
+
+    /// Weights, means and covariances maximizing the likelihood given the responsibilities.
+    fn m_step(
+        &mut self,
+        points: &Points<F>,
+        dim: usize,
+        responsibilities: &[Vec<F>],
+    ) -> Result<(), Error> {
+        let k = self.n_components;
+        // Keep empty components from dividing by zero
+        let mut totals = vec![F::from_f64(10.0) * F::epsilon(); k];
+        let mut means = vec![vec![F::zero(); dim]; k];
+        for (point, responsibility) in points.rows().zip(responsibilities) {
+            for c in 0..k {
+                totals[c] += responsibility[c];
+                for (mean, &x) in means[c].iter_mut().zip(point) {
+                    *mean += responsibility[c] * x;
+                }
+            }
+        }
+        for (mean, &total) in means.iter_mut().zip(&totals) {
+            mean.iter_mut().for_each(|x| *x /= total);
+        }
+        let n = F::from_f64(points.len() as f64);
+        let reg_covar = self.reg_covar;
+        self.covariances = match self.covariance_type {
+            CovarianceType::Full | CovarianceType::Tied => {
+                let mut covariances = vec![vec![F::zero(); dim * dim]; k];
+                for (point, responsibility) in points.rows().zip(responsibilities) {
+                    for c in 0..k {
+                        for i in 0..dim {
+                            let diff_i = responsibility[c] * (point[i] - means[c][i]);
+                            for j in 0..=i {
+                                covariances[c][i * dim + j] += diff_i * (point[j] - means[c][j]);
+                            }
+                        }
+                    }
+                }
+                if self.covariance_type == CovarianceType::Tied {
+                    let mut tied = vec![F::zero(); dim * dim];
+                    for covariance in &covariances {
+                        for (t, &x) in tied.iter_mut().zip(covariance) {
+                            *t += x / n;
+                        }
+                    }
+                    covariances = vec![tied];
+                } else {
+                    for (covariance, &total) in covariances.iter_mut().zip(&totals) {
+                        covariance.iter_mut().for_each(|x| *x /= total);
+                    }
+                }
+                for covariance in &mut covariances {
+                    for i in 0..dim {
+                        covariance[i * dim + i] += reg_covar;
+                        for j in 0..i {
+                            covariance[j * dim + i] = covariance[i * dim + j];
+                        }
+                    }
+                }
+                covariances
+            }
+            CovarianceType::Diag | CovarianceType::Spherical => {
+                let mut variances = vec![vec![F::zero(); dim]; k];
+                for (point, responsibility) in points.rows().zip(responsibilities) {
+                    for c in 0..k {
+                        for i in 0..dim {
+                            let diff = point[i] - means[c][i];
+                            variances[c][i] += responsibility[c] * diff * diff;
+                        }
+                    }
+                }
+                for (variance, &total) in variances.iter_mut().zip(&totals) {
+                    variance
+                        .iter_mut()
+                        .for_each(|x| *x = *x / total + reg_covar);
+                }
+                if self.covariance_type == CovarianceType::Spherical {
+                    let dim = F::from_f64(dim as f64);
+                    variances = variances
+                        .into_iter()
+                        .map(|variance| vec![variance.into_iter().sum::<F>() / dim])
+                        .collect();
+                }
+                variances
+            }
+        };
+        self.cholesky = match self.covariance_type {
+            CovarianceType::Full | CovarianceType::Tied => self
+                .covariances
+                .iter()
+                .enumerate()
+                .map(|(component, covariance)| {
+                    cholesky(covariance, dim).ok_or(Error::IllDefinedCovariance { component })
+                })
+                .collect::<Result<_, _>>()?,
+            CovarianceType::Diag | CovarianceType::Spherical => {
+                if let Some(component) = self.covariances.iter().position(|variances| {
+                    variances.iter().any(|&x| !(x.is_finite() && x > F::zero()))
+                }) {
+                    return Err(Error::IllDefinedCovariance { component });
+                }
+                self.covariances.clone()
+            }
+        };
+        self.weights = totals.into_iter().map(|total| total / n).collect();
+        self.means = means;
+        Ok(())
+    }
+
+    /// The labels of the points of the last fit, their most probable component.
+    pub fn get_labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// The mixing weight of each component, summing to 1.
+    pub fn get_weights(&self) -> &[F] {
+        &self.weights
+    }
+
+    pub fn get_means(&self) -> &[Vec<F>] {
+        &self.means
+    }
+
+    /// The covariances of the components, depending on the covariance type: a row-major
+    /// `dim * dim` matrix per component for full, a single one for tied, the `dim` variances of
+    /// each component for diag, and a single variance per component for spherical.
+    pub fn get_covariances(&self) -> &[Vec<F>] {
+        &self.covariances
+    }
+
+    /// Whether the best run of the last fit converged before `max_iter` iterations.
+    pub fn get_converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Number of iterations run by the best run of the last fit.
+    pub fn get_n_iter(&self) -> usize {
+        self.n_iter
+    }
+
+    /// Mean log-likelihood of the points reached by the best run of the last fit.
+    pub fn get_lower_bound(&self) -> F {
+        self.lower_bound
+    }
+}
+
+/// Lower Cholesky factor of a symmetric `dim * dim` matrix, `None` if not positive definite.
+fn cholesky<F: Float>(matrix: &[F], dim: usize) -> Option<Vec<F>> {
+    let mut lower = vec![F::zero(); dim * dim];
+    for i in 0..dim {
+        for j in 0..=i {
+            let mut sum = matrix[i * dim + j];
+            for l in 0..j {
+                sum = sum - lower[i * dim + l] * lower[j * dim + l];
+            }
+            if i == j {
+                if !(sum.is_finite() && sum > F::zero()) {
+                    return None;
+                }
+                lower[i * dim + i] = sum.sqrt();
+            } else {
+                lower[i * dim + j] = sum / lower[j * dim + j];
+            }
+        }
+    }
+    Some(lower)
+}
+
+/// `ln(Σ exp(x))`, shifted by the largest value so it does not overflow.
+fn log_sum_exp<F: Float>(values: &[F]) -> F {
+    let max = values.iter().copied().fold(F::neg_infinity(), F::max);
+    if max.is_infinite() {
+        return max;
+    }
+    max + values.iter().map(|&x| (x - max).exp()).sum::<F>().ln()
+}
+
+/// Index of the largest value, the first one on ties.
+fn argmax<F: Float>(values: &[F]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .fold((0, F::neg_infinity()), |(best, best_value), (i, &value)| {
+            if value > best_value {
+                (i, value)
+            } else {
+                (best, best_value)
+            }
+        })
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    /// Two Gaussian blobs, 100 points around (0, 0) and 50 narrower ones around (10, 5).
+    fn create_test_points() -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut normal = || {
+            // Box-Muller transform
+            let (u, v): (f64, f64) = (rng.gen_range(1e-12..1.0), rng.gen());
+            (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+        };
+        (0..150)
+            .map(|i| {
+                if i < 100 {
+                    vec![normal(), normal()]
+                } else {
+                    vec![10.0 + 0.5 * normal(), 5.0 + 0.5 * normal()]
+                }
+            })
+            .collect()
+    }
+
+    /// Points centred on the origin, with variances 2 and 4 and a covariance of 4/3.
+    fn create_correlated_points() -> Vec<Vec<f64>> {
+        vec![
+            vec![1.0, 2.0],
+            vec![-1.0, -2.0],
+            vec![1.0, -2.0],
+            vec![-1.0, 2.0],
+            vec![2.0, 2.0],
+            vec![-2.0, -2.0],
+        ]
+    }
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < 1e-5, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_gaussian_mixture_fit() {
+        for covariance_type in [
+            CovarianceType::Full,
+            CovarianceType::Diag,
+            CovarianceType::Spherical,
+            CovarianceType::Tied,
+        ] {
+            let mut gmm = GaussianMixture::new(
+                2,
+                covariance_type,
+                100,
+                CentroidsInitMethod::KmeansPlusPlus,
+                Some(42),
+            );
+            let labels = gmm.fit_predict(create_test_points()).unwrap().to_vec();
+            assert!(gmm.get_converged(), "{covariance_type}");
+            assert!((0..150).all(|i| labels[i] == labels[i / 100 * 100]));
+            assert_ne!(labels[0], labels[100]);
+            let (big, small) = (labels[0], labels[100]);
+            assert!((gmm.get_weights()[big] - 2.0 / 3.0).abs() < 1e-3);
+            assert!((gmm.get_means()[small][0] - 10.0).abs() < 0.2);
+            assert!((gmm.get_means()[small][1] - 5.0).abs() < 0.2);
+
+            let probabilities = gmm
+                .predict_proba(vec![vec![0.0, 0.0], vec![5.0, 2.5]])
+                .unwrap();
+            assert!(probabilities[0][big] > 0.999);
+            assert!((probabilities[1].iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            assert_eq!(
+                gmm.predict(vec![vec![0.5, 0.5], vec![9.5, 5.5]]).unwrap(),
+                [big, small]
+            );
+        }
+    }
+
+    #[test]
+    fn test_gaussian_mixture_covariance_types() {
+        let expected = [
+            (CovarianceType::Full, vec![2.0, 4.0 / 3.0, 4.0 / 3.0, 4.0]),
+            (CovarianceType::Tied, vec![2.0, 4.0 / 3.0, 4.0 / 3.0, 4.0]),
+            (CovarianceType::Diag, vec![2.0, 4.0]),
+            (CovarianceType::Spherical, vec![3.0]),
+        ];
+        for (covariance_type, covariance) in expected {
+            let mut gmm = GaussianMixture::new(
+                1,
+                covariance_type,
+                100,
+                CentroidsInitMethod::Random,
+                Some(0),
+            );
+            gmm.reg_covar = 0.0;
+            gmm.fit(create_correlated_points()).unwrap();
+            assert_close(&gmm.get_means()[0], &[0.0, 0.0]);
+            assert_close(&gmm.get_covariances()[0], &covariance);
+            assert_close(gmm.get_weights(), &[1.0]);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_mixture_log_likelihood() {
+        let mut gmm = GaussianMixture::new(
+            1,
+            CovarianceType::Full,
+            100,
+            CentroidsInitMethod::Random,
+            Some(0),
+        );
+        gmm.reg_covar = 0.0;
+        gmm.fit(create_correlated_points()).unwrap();
+        // ln N(0 | 0, Σ) = -(2 ln 2π + ln det Σ) / 2 with det Σ = 56 / 9
+        let expected = -((2.0 * std::f64::consts::PI).ln() * 2.0 + (56.0_f64 / 9.0).ln()) / 2.0;
+        let scores = gmm.score_samples(vec![vec![0.0, 0.0]]).unwrap();
+        assert!((scores[0] - expected).abs() < 1e-12);
+
+        let points = create_correlated_points();
+        let total: f64 = gmm.score_samples(points.clone()).unwrap().iter().sum();
+        assert!((gmm.score(points.clone()).unwrap() * 6.0 - total).abs() < 1e-12);
+        assert!((gmm.get_lower_bound() * 6.0 - total).abs() < 1e-12);
+        // 2 means and 3 covariance values
+        assert_eq!(gmm.n_parameters(), 5);
+        assert!((gmm.aic(points.clone()).unwrap() - (-2.0 * total + 10.0)).abs() < 1e-12);
+        assert!((gmm.bic(points).unwrap() - (-2.0 * total + 5.0 * 6.0_f64.ln())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gaussian_mixture_model_selection() {
+        let points = create_test_points();
+        let bic: Vec<f64> = (1..=4)
+            .map(|k| {
+                let mut gmm = GaussianMixture::new(
+                    k,
+                    CovarianceType::Full,
+                    100,
+                    CentroidsInitMethod::KmeansPlusPlus,
+                    Some(7),
+                );
+                gmm.n_init = 3;
+                gmm.fit(points.clone()).unwrap();
+                gmm.bic(points.clone()).unwrap()
+            })
+            .collect();
+        let best = argmax(&bic.iter().map(|x| -x).collect::<Vec<_>>());
+        assert_eq!(best + 1, 2, "{bic:?}");
+    }
+
+    #[test]
+    fn test_gaussian_mixture_f32() {
+        let values: Vec<f32> = create_test_points()
+            .concat()
+            .into_iter()
+            .map(|x| x as f32)
+            .collect();
+        let mut gmm = GaussianMixture::new(
+            2,
+            CovarianceType::Diag,
+            100,
+            CentroidsInitMethod::KmeansPlusPlus,
+            Some(1),
+        );
+        let points = Points::view(&values, 2).unwrap();
+        gmm.fit(&points).unwrap();
+        let labels = gmm.get_labels();
+        assert_ne!(labels[0], labels[100]);
+        assert!(gmm.score(&points).unwrap().is_finite());
+    }
+
+    #[test]
+    fn test_gaussian_mixture_errors() {
+        let gmm = GaussianMixture::<f64>::default();
+        assert_eq!(
+            gmm.predict(create_test_points()).unwrap_err(),
+            Error::NotFitted
+        );
+        let mut gmm = GaussianMixture::new(
+            0,
+            CovarianceType::Full,
+            100,
+            CentroidsInitMethod::Random,
+            None,
+        );
+        assert!(matches!(
+            gmm.fit(create_test_points()),
+            Err(Error::InvalidK { .. })
+        ));
+        gmm.n_components = 1;
+        gmm.fit(create_test_points()).unwrap();
+        assert_eq!(
+            gmm.score_samples(vec![vec![0.0]]).unwrap_err(),
+            Error::DimensionMismatch {
+                expected: 2,
+                found: 1
+            }
+        );
+        // Points on a line have a singular covariance without regularization
+        gmm.reg_covar = 0.0;
+        assert_eq!(
+            gmm.fit(vec![vec![0.0, 0.0], vec![1.0, 1.0], vec![2.0, 2.0]]),
+            Err(Error::IllDefinedCovariance { component: 0 })
+        );
+        gmm.reg_covar = -1.0;
+        assert!(matches!(
+            gmm.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        assert_eq!("tied".parse(), Ok(CovarianceType::Tied));
+    }
+}
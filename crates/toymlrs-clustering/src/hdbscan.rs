@@ -0,0 +1,647 @@
+use crate::agglomerative::Merge;
+use crate::dataset::{IntoPoints, Points};
+use crate::distance::{Distance, DistanceMetric};
+use crate::neighbors::core_distances;
+use crate::{Error, Float};
+use parse_display::{Display, FromStr};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+/// How the flat clustering is read from the condensed tree.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, FromStr)]
+pub enum ClusterSelectionMethod {
+    /// Excess of mass, keep the most persistent clusters, either a cluster or its descendants
+    #[default]
+    #[display("eom")]
+    Eom,
+    /// Keep the leaves of the condensed tree, smaller and more homogeneous clusters
+    #[display("leaf")]
+    Leaf,
+}
+
+/// An edge of the condensed tree, from a cluster to a child cluster or to a point leaving it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CondensedTreeEdge<F: Float = f64> {
+    /// Id of the parent cluster, the root being the number of points
+    pub parent: usize,
+    /// Index of the point when below the number of points, id of the child cluster otherwise
+    pub child: usize,
+    /// Inverse of the distance at which the child leaves the parent
+    pub lambda: F,
+    /// Number of points of the child, `1` for a point
+    pub child_size: usize,
+}
+
+/// HDBSCAN, DBSCAN over every `eps` at once, keeping the most persistent clusters.
+///
+/// The distances are replaced by the mutual reachability distances, the largest of the distance
+/// and of the core distances of both points, the core distance of a point being the distance to
+/// its `min_samples`-th nearest point, itself included. The minimum spanning tree of these
+/// distances gives the single linkage hierarchy, condensed by only keeping the splits in two
+/// clusters of at least `min_cluster_size` points; the other splits are points leaving their
+/// cluster. The flat clustering is then selected from the condensed tree, unlike DBSCAN without
+/// a global density threshold, so clusters of varying densities are all found.
+#[derive(Debug)]
+pub struct Hdbscan<F: Float = f64, D: Distance<F> = DistanceMetric> {
+    /// Smallest number of points making a cluster
+    pub min_cluster_size: usize,
+    /// Number of neighbours, the point itself included, for the core distance, defaults to
+    /// `min_cluster_size`
+    pub min_samples: Option<usize>,
+    pub cluster_selection_method: ClusterSelectionMethod,
+    pub distance_metric: D,
+    labels: Vec<Option<usize>>,
+    probabilities: Vec<F>,
+    outlier_scores: Vec<F>,
+    condensed_tree: Vec<CondensedTreeEdge<F>>,
+    n_clusters: usize,
+}
+
+impl<F: Float> Default for Hdbscan<F> {
+    fn default() -> Self {
+        Hdbscan {
+            min_cluster_size: 5,
+            min_samples: None,
+            cluster_selection_method: ClusterSelectionMethod::default(),
+            distance_metric: DistanceMetric::default(),
+            labels: Vec::new(),
+            probabilities: Vec::new(),
+            outlier_scores: Vec::new(),
+            condensed_tree: Vec::new(),
+            n_clusters: 0,
+        }
+    }
+}
+
+impl<F: Float> Hdbscan<F> {
+    pub fn new(
+        min_cluster_size: usize,
+        min_samples: Option<usize>,
+        cluster_selection_method: ClusterSelectionMethod,
+        distance_metric: DistanceMetric,
+    ) -> Self {
+        Hdbscan {
+            min_cluster_size,
+            min_samples,
+            cluster_selection_method,
+            distance_metric,
+            ..Hdbscan::default()
+        }
+    }
+}
+
+impl<F: Float, D: Distance<F>> Hdbscan<F, D> {
+    /// Use a custom distance for the mutual reachability distances.
+    pub fn with_distance_metric<E: Distance<F>>(self, distance_metric: E) -> Hdbscan<F, E> {
+        Hdbscan {
+            min_cluster_size: self.min_cluster_size,
+            min_samples: self.min_samples,
+            cluster_selection_method: self.cluster_selection_method,
+            distance_metric,
+            labels: self.labels,
+            probabilities: self.probabilities,
+            outlier_scores: self.outlier_scores,
+            condensed_tree: self.condensed_tree,
+            n_clusters: self.n_clusters,
+        }
+    }
+
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        if self.min_cluster_size < 2 {
+            return Err(Error::InvalidParameter {
+                name: "min_cluster_size".to_string(),
+                reason: "must be at least 2".to_string(),
+            });
+        }
+        let min_samples = self.min_samples.unwrap_or(self.min_cluster_size);
+        if min_samples == 0 {
+            return Err(Error::InvalidParameter {
+                name: "min_samples".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        let points = points.into_points()?;
+        points.check()?;
+        let n = points.len();
+        if min_samples > n {
+            return Err(Error::InvalidParameter {
+                name: "min_samples".to_string(),
+                reason: format!("must be at most the number of points, {n}"),
+            });
+        }
+        self.n_clusters = 0;
+        self.labels = vec![None; n];
+        self.probabilities = vec![F::zero(); n];
+        self.outlier_scores = vec![F::zero(); n];
+        self.condensed_tree = Vec::new();
+        if n < 2 {
+            return Ok(());
+        }
+
+        let core_distances = core_distances(&points, min_samples, &self.distance_metric);
+        let edges = self.minimum_spanning_tree(&points, &core_distances);
+        let merges = single_linkage(n, edges);
+        self.condensed_tree = condense(n, &merges, self.min_cluster_size);
+        let n_tree_clusters = self
+            .condensed_tree
+            .iter()
+            .map(|edge| edge.parent + 1)
+            .max()
+            .unwrap_or(n + 1)
+            - n;
+
+        // Parent cluster and birth of each cluster, the root being born at lambda 0
+        let mut cluster_parent = vec![None; n_tree_clusters];
+        let mut birth = vec![F::zero(); n_tree_clusters];
+        // Cluster a point leaves and when
+        let mut point_parent = vec![0; n];
+        let mut point_lambda = vec![F::zero(); n];
+        for edge in &self.condensed_tree {
+            if edge.child < n {
+                point_parent[edge.child] = edge.parent - n;
+                point_lambda[edge.child] = edge.lambda;
+            } else {
+                cluster_parent[edge.child - n] = Some(edge.parent - n);
+                birth[edge.child - n] = edge.lambda;
+            }
+        }
+        let mut stability = vec![F::zero(); n_tree_clusters];
+        for edge in &self.condensed_tree {
+            let persistence =
+                (edge.lambda - birth[edge.parent - n]) * F::from_f64(edge.child_size as f64);
+            // Duplicate points are merged at lambda infinity, both ends being infinite
+            if !persistence.is_nan() {
+                stability[edge.parent - n] += persistence;
+            }
+        }
+
+        // Children have larger ids than their parent, the root being 0
+        let mut has_children = vec![false; n_tree_clusters];
+        for parent in cluster_parent.iter().flatten() {
+            has_children[*parent] = true;
+        }
+        let mut selected = vec![false; n_tree_clusters];
+        match self.cluster_selection_method {
+            ClusterSelectionMethod::Eom => {
+                let mut children_stability = vec![F::zero(); n_tree_clusters];
+                for cluster in (1..n_tree_clusters).rev() {
+                    if has_children[cluster] && children_stability[cluster] > stability[cluster] {
+                        stability[cluster] = children_stability[cluster];
+                    } else {
+                        selected[cluster] = true;
+                    }
+                    if let Some(parent) = cluster_parent[cluster] {
+                        children_stability[parent] += stability[cluster];
+                    }
+                }
+                // A selected cluster replaces its selected descendants
+                for cluster in 1..n_tree_clusters {
+                    let mut ancestor = cluster_parent[cluster];
+                    while let Some(a) = ancestor {
+                        if selected[a] {
+                            selected[cluster] = false;
+                            break;
+                        }
+                        ancestor = cluster_parent[a];
+                    }
+                }
+            }
+            ClusterSelectionMethod::Leaf => {
+                for cluster in 1..n_tree_clusters {
+                    selected[cluster] = !has_children[cluster];
+                }
+            }
+        }
+
+        let mut label_of_cluster = vec![None; n_tree_clusters];
+        for cluster in (0..n_tree_clusters).filter(|&c| selected[c]) {
+            label_of_cluster[cluster] = Some(self.n_clusters);
+            self.n_clusters += 1;
+        }
+        // Largest lambda reached by the points of each cluster and of its descendants
+        let mut death = vec![F::zero(); n_tree_clusters];
+        for point in 0..n {
+            let cluster = point_parent[point];
+            death[cluster] = death[cluster].max(point_lambda[point]);
+        }
+        for cluster in (1..n_tree_clusters).rev() {
+            if let Some(parent) = cluster_parent[cluster] {
+                death[parent] = death[parent].max(death[cluster]);
+            }
+        }
+        let mut selected_ancestor = vec![None; n_tree_clusters];
+        for cluster in 1..n_tree_clusters {
+            selected_ancestor[cluster] = if selected[cluster] {
+                Some(cluster)
+            } else {
+                cluster_parent[cluster].and_then(|parent| selected_ancestor[parent])
+            };
+        }
+        let mut max_lambda = vec![F::zero(); n_tree_clusters];
+        for point in 0..n {
+            if let Some(cluster) = selected_ancestor[point_parent[point]] {
+                max_lambda[cluster] = max_lambda[cluster].max(point_lambda[point]);
+            }
+        }
+
+        for point in 0..n {
+            let lambda = point_lambda[point];
+            if let Some(cluster) = selected_ancestor[point_parent[point]] {
+                self.labels[point] = label_of_cluster[cluster];
+                let max = max_lambda[cluster];
+                self.probabilities[point] = if max == F::zero() || lambda.is_infinite() {
+                    F::one()
+                } else {
+                    lambda.min(max) / max
+                };
+            }
+            // GLOSH, how far the point is from the densest part of the cluster it leaves
+            let max = death[point_parent[point]];
+            self.outlier_scores[point] = if max == F::zero() || lambda.is_infinite() {
+                F::zero()
+            } else if max.is_infinite() {
+                F::one()
+            } else {
+                (max - lambda) / max
+            };
+        }
+        Ok(())
+    }
+
+    /// Prim's algorithm over the mutual reachability distances, as `(a, b, distance)` edges.
+    fn minimum_spanning_tree(
+        &self,
+        points: &Points<F>,
+        core_distances: &[F],
+    ) -> Vec<(usize, usize, F)> {
+        let n = points.len();
+        let mut in_tree = vec![false; n];
+        let mut best = vec![F::infinity(); n];
+        let mut best_from = vec![0; n];
+        let mut edges = Vec::with_capacity(n - 1);
+        let mut current = 0;
+        for _ in 1..n {
+            in_tree[current] = true;
+            let point = points.row(current);
+            #[cfg(feature = "parallel")]
+            let rows = points.par_rows();
+            #[cfg(not(feature = "parallel"))]
+            let rows = points.rows();
+            let distances: Vec<F> = rows
+                .map(|other| self.distance_metric.distance(point, other))
+                .collect();
+            let mut next = None;
+            for other in (0..n).filter(|&i| !in_tree[i]) {
+                let distance = distances[other]
+                    .max(core_distances[current])
+                    .max(core_distances[other]);
+                if distance < best[other] {
+                    best[other] = distance;
+                    best_from[other] = current;
+                }
+                if next.is_none_or(|next: usize| best[other] < best[next]) {
+                    next = Some(other);
+                }
+            }
+            // There is always a point left outside the tree
+            let next = next.unwrap_or_default();
+            edges.push((best_from[next], next, best[next]));
+            current = next;
+        }
+        edges
+    }
+
+    /// The cluster of each point of the last fit, `None` for noise.
+    pub fn get_labels(&self) -> &[Option<usize>] {
+        &self.labels
+    }
+
+    /// How strongly each point of the last fit belongs to its cluster, from 0 for noise to 1.
+    pub fn get_probabilities(&self) -> &[F] {
+        &self.probabilities
+    }
+
+    /// GLOSH outlier score of each point of the last fit, from 0 to 1 for the strongest outliers.
+    pub fn get_outlier_scores(&self) -> &[F] {
+        &self.outlier_scores
+    }
+
+    /// The condensed tree of the last fit, parents before their children.
+    pub fn get_condensed_tree(&self) -> &[CondensedTreeEdge<F>] {
+        &self.condensed_tree
+    }
+
+    pub fn get_n_clusters(&self) -> usize {
+        self.n_clusters
+    }
+}
+
+fn find(parents: &mut [usize], mut node: usize) -> usize {
+    let mut root = node;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    while parents[node] != root {
+        let next = parents[node];
+        parents[node] = root;
+        node = next;
+    }
+    root
+}
+
+/// Merge the points along the minimum spanning tree edges, by increasing distance.
+fn single_linkage<F: Float>(n: usize, mut edges: Vec<(usize, usize, F)>) -> Vec<Merge<F>> {
+    edges.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(Ordering::Equal));
+    let mut parents: Vec<usize> = (0..2 * n - 1).collect();
+    let mut sizes = vec![1; 2 * n - 1];
+    edges
+        .into_iter()
+        .enumerate()
+        .map(|(i, (a, b, distance))| {
+            let (left, right) = (find(&mut parents, a), find(&mut parents, b));
+            parents[left] = n + i;
+            parents[right] = n + i;
+            sizes[n + i] = sizes[left] + sizes[right];
+            Merge {
+                left,
+                right,
+                distance,
+                size: sizes[n + i],
+            }
+        })
+        .collect()
+}
+
+/// Points below a node of the single linkage hierarchy.
+fn leaves<F: Float>(n: usize, merges: &[Merge<F>], node: usize) -> Vec<usize> {
+    let mut leaves = Vec::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if node < n {
+            leaves.push(node);
+        } else {
+            stack.push(merges[node - n].left);
+            stack.push(merges[node - n].right);
+        }
+    }
+    leaves
+}
+
+/// Walk the hierarchy from the root, only keeping the splits in two large enough clusters.
+///
+/// Clusters are numbered from `n`, the root, as they are found, so children come after their
+/// parent; everything below a cluster too small to keep leaves the parent at once.
+fn condense<F: Float>(
+    n: usize,
+    merges: &[Merge<F>],
+    min_cluster_size: usize,
+) -> Vec<CondensedTreeEdge<F>> {
+    let size = |node: usize| if node < n { 1 } else { merges[node - n].size };
+    let mut relabel = vec![0; 2 * n - 1];
+    relabel[2 * n - 2] = n;
+    let mut next_label = n + 1;
+    let mut ignored = vec![false; n - 1];
+    let mut tree = Vec::new();
+    // Merges are in increasing distance, so a node comes before its children in reverse
+    for node in (n..2 * n - 1).rev() {
+        if ignored[node - n] {
+            continue;
+        }
+        let merge = &merges[node - n];
+        let lambda = if merge.distance > F::zero() {
+            merge.distance.recip()
+        } else {
+            F::infinity()
+        };
+        let parent = relabel[node];
+        let (left_size, right_size) = (size(merge.left), size(merge.right));
+        let left_kept = left_size >= min_cluster_size;
+        let right_kept = right_size >= min_cluster_size;
+        for (child, child_size, kept) in [
+            (merge.left, left_size, left_kept),
+            (merge.right, right_size, right_kept),
+        ] {
+            if kept && left_kept && right_kept {
+                relabel[child] = next_label;
+                next_label += 1;
+                tree.push(CondensedTreeEdge {
+                    parent,
+                    child: relabel[child],
+                    lambda,
+                    child_size,
+                });
+            } else if kept {
+                // The cluster goes on, only losing the points of the other side
+                relabel[child] = parent;
+            } else {
+                for point in leaves(n, merges, child) {
+                    tree.push(CondensedTreeEdge {
+                        parent,
+                        child: point,
+                        lambda,
+                        child_size: 1,
+                    });
+                }
+                let mut stack = vec![child];
+                while let Some(node) = stack.pop() {
+                    if node >= n {
+                        ignored[node - n] = true;
+                        stack.push(merges[node - n].left);
+                        stack.push(merges[node - n].right);
+                    }
+                }
+            }
+        }
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A disc of `n` points spread like sunflower seeds, `spacing` apart, around `centre`.
+    fn disc(centre: (f64, f64), n: usize, spacing: f64) -> Vec<Vec<f64>> {
+        let golden_angle = std::f64::consts::PI * (3.0 - 5.0_f64.sqrt());
+        (0..n)
+            .map(|i| {
+                let radius = spacing * (i as f64 + 0.5).sqrt();
+                let angle = i as f64 * golden_angle;
+                vec![
+                    centre.0 + radius * angle.cos(),
+                    centre.1 + radius * angle.sin(),
+                ]
+            })
+            .collect()
+    }
+
+    fn create_test_points() -> Vec<Vec<f64>> {
+        // A dense and a sparse blob, no single eps fits both
+        let mut points = disc((0.0, 0.0), 25, 0.1);
+        points.extend(disc((10.0, 10.0), 25, 1.0));
+        // Outliers
+        points.push(vec![40.0, -20.0]);
+        points.push(vec![-30.0, 30.0]);
+        points
+    }
+
+    #[test]
+    fn test_hdbscan_fit() {
+        let mut hdbscan = Hdbscan::new(
+            5,
+            None,
+            ClusterSelectionMethod::Eom,
+            DistanceMetric::Euclidean,
+        );
+        hdbscan.fit(create_test_points()).unwrap();
+        assert_eq!(hdbscan.get_n_clusters(), 2);
+        let labels = hdbscan.get_labels();
+        assert!(labels[..25].iter().all(|&label| label == Some(0)));
+        assert!(labels[25..50].iter().all(|&label| label == Some(1)));
+        assert_eq!(labels[50..], [None, None]);
+
+        let probabilities = hdbscan.get_probabilities();
+        // The centre of the discs is their densest part
+        assert_eq!(probabilities[0], 1.0);
+        assert_eq!(probabilities[25], 1.0);
+        assert!(probabilities[..50].iter().all(|&p| p > 0.0 && p <= 1.0));
+        assert_eq!(probabilities[50..], [0.0, 0.0]);
+
+        let outlier_scores = hdbscan.get_outlier_scores();
+        assert_eq!(outlier_scores[0], 0.0);
+        assert!(outlier_scores[..50].iter().all(|&score| score < 0.9));
+        assert!(outlier_scores[50..].iter().all(|&score| score > 0.9));
+    }
+
+    #[test]
+    fn test_hdbscan_condensed_tree() {
+        let points = create_test_points();
+        let n = points.len();
+        let mut hdbscan = Hdbscan::<f64>::default();
+        hdbscan.fit(points).unwrap();
+        let tree = hdbscan.get_condensed_tree();
+        // Every point leaves a cluster exactly once
+        let mut counts = vec![0; n];
+        for edge in tree.iter().filter(|edge| edge.child < n) {
+            counts[edge.child] += 1;
+            assert_eq!(edge.child_size, 1);
+        }
+        assert!(counts.iter().all(|&count| count == 1));
+        // Clusters split in two, born after their parent with all its points but the outliers
+        let children: Vec<_> = tree.iter().filter(|edge| edge.child >= n).collect();
+        assert_eq!(children.len(), 2);
+        assert!(children
+            .iter()
+            .all(|edge| edge.parent == n && edge.child_size == 25));
+        for edge in tree {
+            assert!(edge.child < n || edge.child > edge.parent);
+            assert!(edge.lambda > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_hdbscan_leaf_selection() {
+        // Two close dense discs, next to a third far away
+        let mut points = disc((0.0, 0.0), 16, 0.1);
+        points.extend(disc((1.0, 0.0), 16, 0.1));
+        points.extend(disc((20.0, 20.0), 16, 0.1));
+        let mut hdbscan = Hdbscan::new(
+            5,
+            Some(3),
+            ClusterSelectionMethod::Eom,
+            DistanceMetric::Euclidean,
+        );
+        hdbscan.fit(points.clone()).unwrap();
+        // The close discs together outlast their separate lives
+        assert_eq!(hdbscan.get_n_clusters(), 2);
+        let labels = hdbscan.get_labels();
+        assert!((0..32).all(|i| labels[i] == Some(0)));
+
+        hdbscan.cluster_selection_method = ClusterSelectionMethod::Leaf;
+        hdbscan.fit(points).unwrap();
+        assert_eq!(hdbscan.get_n_clusters(), 3);
+        let labels = hdbscan.get_labels();
+        assert!((0..48).all(|i| labels[i] == labels[i / 16 * 16]));
+        assert_ne!(labels[0], labels[16]);
+        assert_ne!(labels[16], labels[32]);
+    }
+
+    #[test]
+    fn test_hdbscan_metric_and_f32() {
+        let values: Vec<f32> = create_test_points()
+            .concat()
+            .into_iter()
+            .map(|x| x as f32)
+            .collect();
+        let mut hdbscan = Hdbscan::new(
+            5,
+            Some(4),
+            ClusterSelectionMethod::Eom,
+            DistanceMetric::Manhattan,
+        );
+        hdbscan.fit(Points::view(&values, 2).unwrap()).unwrap();
+        assert_eq!(hdbscan.get_n_clusters(), 2);
+        assert_eq!(hdbscan.get_labels()[51], None);
+
+        // Duplicate points are at distance 0, so at lambda infinity
+        let mut hdbscan = Hdbscan::new(
+            2,
+            Some(1),
+            ClusterSelectionMethod::Eom,
+            DistanceMetric::Euclidean,
+        );
+        hdbscan
+            .fit(vec![
+                vec![0.0, 0.0],
+                vec![0.0, 0.0],
+                vec![5.0, 5.0],
+                vec![5.0, 5.0],
+            ])
+            .unwrap();
+        assert_eq!(hdbscan.get_labels(), [Some(0), Some(0), Some(1), Some(1)]);
+        assert_eq!(hdbscan.get_probabilities(), [1.0; 4]);
+    }
+
+    #[test]
+    fn test_hdbscan_errors() {
+        let mut hdbscan = Hdbscan::new(
+            1,
+            None,
+            ClusterSelectionMethod::Eom,
+            DistanceMetric::Euclidean,
+        );
+        assert!(matches!(
+            hdbscan.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        let mut hdbscan = Hdbscan::new(
+            5,
+            Some(0),
+            ClusterSelectionMethod::Eom,
+            DistanceMetric::Euclidean,
+        );
+        assert!(matches!(
+            hdbscan.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        let mut hdbscan = Hdbscan::new(
+            5,
+            None,
+            ClusterSelectionMethod::Eom,
+            DistanceMetric::Minkowski(0.0),
+        );
+        assert!(matches!(
+            hdbscan.fit(create_test_points()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        let mut hdbscan = Hdbscan::<f64>::default();
+        assert!(matches!(
+            hdbscan.fit(vec![vec![0.0, 0.0], vec![1.0, 1.0]]),
+            Err(Error::InvalidParameter { .. })
+        ));
+        assert_eq!(hdbscan.fit(vec![]), Err(Error::EmptyDataset));
+        assert_eq!("leaf".parse(), Ok(ClusterSelectionMethod::Leaf));
+    }
+}
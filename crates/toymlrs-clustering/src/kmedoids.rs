@@ -0,0 +1,563 @@
+use crate::dataset::{IntoPoints, Points};
+use crate::distance::{Distance, DistanceMetric};
+use crate::{Error, Float};
+use parse_display::{Display, FromStr};
+use rand::prelude::SeedableRng;
+use rand::random;
+use rand::seq::index;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// How the medoids are searched for.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, FromStr)]
+pub enum KmedoidsAlgorithm {
+    /// Greedy BUILD initialization, then the best swap of a medoid and a point at each iteration
+    #[display("pam")]
+    Pam,
+    /// Random initialization, then every improving swap as soon as it is found, much faster
+    /// than PAM on large datasets for a similar quality
+    #[default]
+    #[display("fasterpam")]
+    FasterPam,
+}
+
+/// K-medoids clustering, whose cluster representatives are points of the dataset.
+///
+/// The medoids minimize the sum of the distances from each point to its nearest medoid, so any
+/// dissimilarity works, including a precomputed distance matrix, where averaging the points of
+/// a cluster would be meaningless.
+#[derive(Debug)]
+pub struct Kmedoids<F: Float = f64, D: Distance<F> = DistanceMetric> {
+    pub k: usize,
+    /// Largest number of swaps for PAM, of passes over the points for FasterPAM
+    pub max_iter: usize,
+    pub algorithm: KmedoidsAlgorithm,
+    pub random_seed: Option<u64>,
+    pub distance_metric: D,
+    medoid_indices: Vec<usize>,
+    medoids: Vec<Vec<F>>,
+    labels: Vec<usize>,
+    inertia: F,
+    n_iter: usize,
+}
+
+impl<F: Float> Default for Kmedoids<F> {
+    fn default() -> Self {
+        Kmedoids {
+            k: 2,
+            max_iter: 100,
+            algorithm: KmedoidsAlgorithm::default(),
+            random_seed: None,
+            distance_metric: DistanceMetric::default(),
+            medoid_indices: Vec::new(),
+            medoids: Vec::new(),
+            labels: Vec::new(),
+            inertia: F::zero(),
+            n_iter: 0,
+        }
+    }
+}
+
+impl<F: Float> Kmedoids<F> {
+    pub fn new(
+        k: usize,
+        max_iter: usize,
+        algorithm: KmedoidsAlgorithm,
+        distance_metric: DistanceMetric,
+        random_seed: Option<u64>,
+    ) -> Self {
+        Kmedoids {
+            k,
+            max_iter,
+            algorithm,
+            distance_metric,
+            random_seed,
+            ..Kmedoids::default()
+        }
+    }
+}
+
+/// Distance from each point to its nearest and second nearest medoids.
+struct Assignment<F> {
+    nearest: Vec<usize>,
+    nearest_distance: Vec<F>,
+    second_distance: Vec<F>,
+}
+
+impl<F: Float> Assignment<F> {
+    fn new(distances: &Points<F>, medoids: &[usize]) -> Self {
+        let mut assignment = Assignment {
+            nearest: vec![0; distances.len()],
+            nearest_distance: vec![F::infinity(); distances.len()],
+            second_distance: vec![F::infinity(); distances.len()],
+        };
+        for (point, row) in distances.rows().enumerate() {
+            for (medoid, &index) in medoids.iter().enumerate() {
+                let distance = row[index];
+                if distance < assignment.nearest_distance[point] {
+                    assignment.second_distance[point] = assignment.nearest_distance[point];
+                    assignment.nearest[point] = medoid;
+                    assignment.nearest_distance[point] = distance;
+                } else if distance < assignment.second_distance[point] {
+                    assignment.second_distance[point] = distance;
+                }
+            }
+        }
+        assignment
+    }
+}
+
+impl<F: Float, D: Distance<F>> Kmedoids<F, D> {
+    /// Use a custom distance between the points.
+    pub fn with_distance_metric<E: Distance<F>>(self, distance_metric: E) -> Kmedoids<F, E> {
+        Kmedoids {
+            k: self.k,
+            max_iter: self.max_iter,
+            algorithm: self.algorithm,
+            random_seed: self.random_seed,
+            distance_metric,
+            medoid_indices: self.medoid_indices,
+            medoids: self.medoids,
+            labels: self.labels,
+            inertia: self.inertia,
+            n_iter: self.n_iter,
+        }
+    }
+
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        let points = points.into_points()?;
+        points.check()?;
+        #[cfg(feature = "parallel")]
+        let rows = points.par_rows();
+        #[cfg(not(feature = "parallel"))]
+        let rows = points.rows();
+        let distances: Vec<Vec<F>> = rows
+            .map(|point| {
+                points
+                    .rows()
+                    .map(|other| self.distance_metric.distance(point, other))
+                    .collect()
+            })
+            .collect();
+        self.fit_distances(&Points::new(distances.concat(), points.len())?)?;
+        self.medoids = self
+            .medoid_indices
+            .iter()
+            .map(|&index| points.row(index).to_vec())
+            .collect();
+        Ok(())
+    }
+
+    /// Fit from the square matrix of the distances between the points, e.g. edit distances.
+    ///
+    /// Row `i` holds the distances from the `i`-th point; the matrix needs not be symmetric, the
+    /// distance from a point to a medoid being read in the row of the point.
+    pub fn fit_precomputed<'a>(&mut self, distances: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        let distances = distances.into_points()?;
+        let found = distances.check()?;
+        if found != distances.len() {
+            return Err(Error::DimensionMismatch {
+                expected: distances.len(),
+                found,
+            });
+        }
+        if distances.as_slice().iter().any(|&d| d < F::zero()) {
+            return Err(Error::InvalidParameter {
+                name: "distances".to_string(),
+                reason: "must be non-negative".to_string(),
+            });
+        }
+        self.fit_distances(&distances)?;
+        self.medoids = Vec::new();
+        Ok(())
+    }
+
+    fn fit_distances(&mut self, distances: &Points<F>) -> Result<(), Error> {
+        let n = distances.len();
+        if self.k == 0 || self.k > n {
+            return Err(Error::InvalidK {
+                k: self.k,
+                n_samples: n,
+            });
+        }
+        self.n_iter = 0;
+        self.medoid_indices = match self.algorithm {
+            KmedoidsAlgorithm::Pam => {
+                let mut medoids = build(distances, self.k);
+                self.pam_swap(distances, &mut medoids);
+                medoids
+            }
+            // The single best medoid is the first one BUILD picks
+            KmedoidsAlgorithm::FasterPam if self.k == 1 => build(distances, 1),
+            KmedoidsAlgorithm::FasterPam => {
+                let mut rng =
+                    rand::rngs::StdRng::seed_from_u64(self.random_seed.unwrap_or(random::<u64>()));
+                let mut medoids = index::sample(&mut rng, n, self.k).into_vec();
+                self.faster_pam_swap(distances, &mut medoids);
+                medoids
+            }
+        };
+        let assignment = Assignment::new(distances, &self.medoid_indices);
+        self.inertia = assignment.nearest_distance.iter().copied().sum();
+        self.labels = assignment.nearest;
+        Ok(())
+    }
+
+    /// Repeatedly apply the swap of a medoid and a point decreasing the most the total distance.
+    fn pam_swap(&mut self, distances: &Points<F>, medoids: &mut [usize]) {
+        let n = distances.len();
+        let mut assignment = Assignment::new(distances, medoids);
+        while self.n_iter < self.max_iter {
+            let mut best = (F::zero(), 0, 0);
+            for candidate in (0..n).filter(|i| !medoids.contains(i)) {
+                for medoid in 0..medoids.len() {
+                    let change = (0..n)
+                        .map(|point| {
+                            let distance = distances.row(point)[candidate];
+                            let nearest = assignment.nearest_distance[point];
+                            if assignment.nearest[point] == medoid {
+                                distance.min(assignment.second_distance[point]) - nearest
+                            } else {
+                                (distance - nearest).min(F::zero())
+                            }
+                        })
+                        .sum::<F>();
+                    if change < best.0 {
+                        best = (change, medoid, candidate);
+                    }
+                }
+            }
+            if best.0 >= F::zero() {
+                break;
+            }
+            self.n_iter += 1;
+            medoids[best.1] = best.2;
+            assignment = Assignment::new(distances, medoids);
+        }
+    }
+
+    /// Pass over the points as swap candidates, swapping with the best medoid as soon as it
+    /// decreases the total distance, until a whole pass finds no improving swap.
+    fn faster_pam_swap(&mut self, distances: &Points<F>, medoids: &mut [usize]) {
+        let n = distances.len();
+        let k = medoids.len();
+        let mut assignment = Assignment::new(distances, medoids);
+        // Increase of the total distance when removing each medoid
+        let removal_loss = |assignment: &Assignment<F>| {
+            let mut loss = vec![F::zero(); k];
+            for point in 0..n {
+                loss[assignment.nearest[point]] +=
+                    assignment.second_distance[point] - assignment.nearest_distance[point];
+            }
+            loss
+        };
+        let mut loss = removal_loss(&assignment);
+        let mut candidate = 0;
+        // Number of candidates checked since the last swap
+        let mut n_checked = 0;
+        while self.n_iter < self.max_iter {
+            if candidate == 0 {
+                self.n_iter += 1;
+            }
+            if !medoids.contains(&candidate) {
+                let mut change = loss.clone();
+                let mut shared = F::zero();
+                for point in 0..n {
+                    let distance = distances.row(point)[candidate];
+                    let nearest = assignment.nearest_distance[point];
+                    let second = assignment.second_distance[point];
+                    if distance < nearest {
+                        // The point moves to the candidate, whichever medoid leaves, so losing
+                        // its medoid no longer sends it to the second nearest
+                        shared += distance - nearest;
+                        change[assignment.nearest[point]] += nearest - second;
+                    } else if distance < second {
+                        change[assignment.nearest[point]] += distance - second;
+                    }
+                }
+                let (medoid, best) = change.iter().enumerate().fold(
+                    (0, F::infinity()),
+                    |(medoid, best), (i, &c)| {
+                        if c < best {
+                            (i, c)
+                        } else {
+                            (medoid, best)
+                        }
+                    },
+                );
+                if best + shared < F::zero() {
+                    medoids[medoid] = candidate;
+                    assignment = Assignment::new(distances, medoids);
+                    loss = removal_loss(&assignment);
+                    n_checked = 0;
+                }
+            }
+            candidate = (candidate + 1) % n;
+            n_checked += 1;
+            if n_checked >= n {
+                break;
+            }
+        }
+    }
+
+    pub fn fit_predict<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<&[usize], Error> {
+        self.fit(points)?;
+        Ok(&self.labels)
+    }
+
+    /// Assign each point to its nearest medoid, for a model fitted with [`Kmedoids::fit`].
+    pub fn predict<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Vec<usize>, Error> {
+        let expected = self.medoids.first().ok_or(Error::NotFitted)?.len();
+        let points = points.into_points()?;
+        let found = points.check()?;
+        if found != expected {
+            return Err(Error::DimensionMismatch { expected, found });
+        }
+        Ok(points
+            .rows()
+            .map(|point| {
+                self.medoids
+                    .iter()
+                    .enumerate()
+                    .fold((0, F::infinity()), |(nearest, best), (i, medoid)| {
+                        let distance = self.distance_metric.distance(point, medoid);
+                        if distance < best {
+                            (i, distance)
+                        } else {
+                            (nearest, best)
+                        }
+                    })
+                    .0
+            })
+            .collect())
+    }
+
+    /// Index in the dataset of the medoid of each cluster.
+    pub fn get_medoid_indices(&self) -> &[usize] {
+        &self.medoid_indices
+    }
+
+    /// The medoids of the last fit, empty when fitted from precomputed distances.
+    pub fn get_medoids(&self) -> &[Vec<F>] {
+        &self.medoids
+    }
+
+    /// The cluster of each point of the last fit, the position of its medoid.
+    pub fn get_labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// Sum of the distances from each point to its medoid.
+    pub fn get_inertia(&self) -> F {
+        self.inertia
+    }
+
+    /// Number of swaps for PAM, of passes over the points for FasterPAM, of the last fit.
+    pub fn get_n_iter(&self) -> usize {
+        self.n_iter
+    }
+}
+
+/// Greedily add the medoid decreasing the most the total distance, starting from the point
+/// with the smallest total distance to the others.
+fn build<F: Float>(distances: &Points<F>, k: usize) -> Vec<usize> {
+    let n = distances.len();
+    let mut medoids = Vec::with_capacity(k);
+    let mut nearest = vec![F::infinity(); n];
+    for _ in 0..k {
+        let mut best = (F::infinity(), 0);
+        for candidate in (0..n).filter(|i| !medoids.contains(i)) {
+            let total = (0..n)
+                .map(|point| distances.row(point)[candidate].min(nearest[point]))
+                .sum::<F>();
+            if total < best.0 {
+                best = (total, candidate);
+            }
+        }
+        medoids.push(best.1);
+        for (point, nearest) in nearest.iter_mut().enumerate() {
+            *nearest = nearest.min(distances.row(point)[best.1]);
+        }
+    }
+    medoids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn create_test_points() -> Vec<Vec<f64>> {
+        vec![
+            vec![1.0, 1.0],
+            vec![1.5, 2.0],
+            vec![3.0, 4.0],
+            vec![5.0, 7.0],
+            vec![3.5, 5.0],
+            vec![4.5, 5.0],
+            vec![3.5, 4.5],
+        ]
+    }
+
+    fn levenshtein(a: &str, b: &str) -> f64 {
+        let b: Vec<char> = b.chars().collect();
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = i + 1;
+            for (j, &cb) in b.iter().enumerate() {
+                let substitution = diagonal + usize::from(ca != cb);
+                diagonal = row[j + 1];
+                row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+            }
+        }
+        row[b.len()] as f64
+    }
+
+    /// Smallest total distance over every set of `k` medoids.
+    fn brute_force_inertia(distances: &[Vec<f64>], k: usize) -> f64 {
+        let n = distances.len();
+        (0..1_usize << n)
+            .filter(|subset| subset.count_ones() as usize == k)
+            .map(|subset| {
+                distances
+                    .iter()
+                    .map(|row| {
+                        (0..n)
+                            .filter(|&j| subset & (1 << j) != 0)
+                            .map(|j| row[j])
+                            .fold(f64::INFINITY, f64::min)
+                    })
+                    .sum()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn test_kmedoids_fit() {
+        for algorithm in [KmedoidsAlgorithm::Pam, KmedoidsAlgorithm::FasterPam] {
+            let mut kmedoids =
+                Kmedoids::new(2, 100, algorithm, DistanceMetric::Euclidean, Some(42));
+            let labels = kmedoids.fit_predict(create_test_points()).unwrap().to_vec();
+            let mut medoid_indices = kmedoids.get_medoid_indices().to_vec();
+            medoid_indices.sort();
+            assert_eq!(medoid_indices, [0, 4], "{algorithm}");
+            assert!((0..7).all(|i| (labels[i] == labels[0]) == (i < 2)));
+            assert_eq!(kmedoids.get_medoids()[labels[0]], [1.0, 1.0]);
+            assert_eq!(
+                kmedoids
+                    .predict(vec![vec![0.0, 0.0], vec![4.0, 6.0]])
+                    .unwrap(),
+                [labels[0], labels[2]]
+            );
+        }
+    }
+
+    #[test]
+    fn test_kmedoids_precomputed_edit_distances() {
+        let words = [
+            "kitten", "sitting", "mitten", "bitten", "apple", "apply", "ample", "maple",
+        ];
+        let distances: Vec<Vec<f64>> = words
+            .iter()
+            .map(|a| words.iter().map(|b| levenshtein(a, b)).collect())
+            .collect();
+        for algorithm in [KmedoidsAlgorithm::Pam, KmedoidsAlgorithm::FasterPam] {
+            let mut kmedoids = Kmedoids::new(2, 100, algorithm, DistanceMetric::Euclidean, Some(3));
+            kmedoids.fit_precomputed(distances.clone()).unwrap();
+            let labels = kmedoids.get_labels();
+            assert!(
+                (0..8).all(|i| (labels[i] == labels[0]) == (i < 4)),
+                "{algorithm}"
+            );
+            assert_eq!(
+                kmedoids.get_inertia(),
+                brute_force_inertia(&distances, 2),
+                "{algorithm}"
+            );
+            assert!(kmedoids.get_medoids().is_empty());
+            assert_eq!(
+                kmedoids.predict(vec![vec![0.0; 8]]).unwrap_err(),
+                Error::NotFitted
+            );
+        }
+    }
+
+    #[test]
+    fn test_kmedoids_reaches_optimum() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let points: Vec<Vec<f64>> = (0..14)
+            .map(|_| vec![rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0)])
+            .collect();
+        let distances: Vec<Vec<f64>> = points
+            .iter()
+            .map(|a| {
+                points
+                    .iter()
+                    .map(|b| DistanceMetric::Manhattan.distance(a, b))
+                    .collect()
+            })
+            .collect();
+        for k in 1..=4 {
+            let optimum = brute_force_inertia(&distances, k);
+            for algorithm in [KmedoidsAlgorithm::Pam, KmedoidsAlgorithm::FasterPam] {
+                let mut kmedoids =
+                    Kmedoids::new(k, 100, algorithm, DistanceMetric::Manhattan, Some(11));
+                kmedoids.fit(points.clone()).unwrap();
+                assert!(
+                    (kmedoids.get_inertia() - optimum).abs() < 1e-9,
+                    "{algorithm} {k}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_kmedoids_f32() {
+        let values: Vec<f32> = create_test_points()
+            .concat()
+            .into_iter()
+            .map(|x| x as f32)
+            .collect();
+        let mut kmedoids = Kmedoids::new(
+            2,
+            100,
+            KmedoidsAlgorithm::Pam,
+            DistanceMetric::Euclidean,
+            None,
+        );
+        kmedoids.fit(Points::view(&values, 2).unwrap()).unwrap();
+        assert_eq!(kmedoids.get_medoid_indices(), [4, 0]);
+        assert!(kmedoids.get_n_iter() <= 1);
+    }
+
+    #[test]
+    fn test_kmedoids_errors() {
+        let mut kmedoids = Kmedoids::<f64>::default();
+        assert_eq!(
+            kmedoids.fit_precomputed(vec![vec![0.0, 1.0, 2.0], vec![1.0, 0.0, 3.0]]),
+            Err(Error::DimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert!(matches!(
+            kmedoids.fit_precomputed(vec![vec![0.0, -1.0], vec![-1.0, 0.0]]),
+            Err(Error::InvalidParameter { .. })
+        ));
+        kmedoids.k = 8;
+        assert_eq!(
+            kmedoids.fit(create_test_points()),
+            Err(Error::InvalidK { k: 8, n_samples: 7 })
+        );
+        assert_eq!(kmedoids.fit(vec![]), Err(Error::EmptyDataset));
+        kmedoids.k = 2;
+        kmedoids.distance_metric = DistanceMetric::Minkowski(0.0);
+        assert!(matches!(
+            kmedoids.fit(create_test_points()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        assert_eq!("fasterpam".parse(), Ok(KmedoidsAlgorithm::FasterPam));
+    }
+}
@@ -1,4 +1,26 @@
+pub mod agglomerative;
+#[cfg(feature = "ndarray")]
+pub mod array;
+mod bounds;
+pub mod dataset;
+pub mod dbscan;
+pub mod distance;
+pub mod error;
+pub mod float;
+pub mod gaussian_mixture;
+pub mod hdbscan;
 pub mod kmeans;
+pub mod kmedoids;
+pub mod mean_shift;
+pub mod mini_batch_kmeans;
+mod neighbors;
+pub mod optics;
+#[cfg(feature = "serde")]
+pub mod persistence;
+pub mod spectral;
+
+pub use error::Error;
+pub use float::Float;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
@@ -1,16 +1,417 @@
-use toymlrs_clustering::kmeans::{CentroidsInitMethod, Kmeans};
-
-fn main() {
-    let points = vec![
-        vec![1.0, 0.0],
-        vec![1.0, 1.0],
-        vec![1.0, 2.0],
-        vec![10.0, 0.0],
-        vec![10.0, 1.0],
-        vec![10.0, 2.0],
-    ];
-    let mut kmeans = Kmeans::default();
-    kmeans.centroids_init_method = CentroidsInitMethod::KmeansPlusPlus;
-    kmeans.fit(points);
-    println!("Clusters: {:?}", kmeans.get_labels());
+//! Command-line k-means clustering of CSV/TSV data.
+//!
+//! ```text
+//! toymlrs-clustering fit points.csv --k 3 --header --columns x,y --save model.json
+//! toymlrs-clustering predict new_points.csv --model model.json --header --columns x,y --append
+//! ```
+
+use clap::{Args, Parser, Subcommand};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use toymlrs_clustering::kmeans::{CentroidsInitMethod, DistanceMetric, Kmeans};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "K-means clustering of CSV/TSV data")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Fit a model and write the label of each row
+    Fit(FitArgs),
+    /// Write the label of each row with a model saved by `fit --save`
+    Predict(PredictArgs),
+}
+
+#[derive(Debug, Args)]
+struct InputArgs {
+    /// CSV or TSV file, read from stdin when missing or `-`
+    input: Option<PathBuf>,
+    /// Field delimiter, defaults to a tab for `.tsv` files and to a comma otherwise
+    #[arg(long)]
+    delimiter: Option<char>,
+    /// The first line holds the column names
+    #[arg(long)]
+    header: bool,
+    /// Columns holding the point values, by name (with `--header`) or 0-based index, all by default
+    #[arg(long, value_delimiter = ',')]
+    columns: Vec<String>,
+    /// Write the input rows with an appended label column instead of the labels only
+    #[arg(long)]
+    append: bool,
+}
+
+#[derive(Debug, Args)]
+struct FitArgs {
+    #[command(flatten)]
+    input: InputArgs,
+    /// Number of clusters
+    #[arg(long)]
+    k: usize,
+    /// Centroid seeding: random, kmeans++, greedy-kmeans++ or kmeans||
+    #[arg(long, default_value = "kmeans++")]
+    init: CentroidsInitMethod,
+    /// Distance: euclidean, sqeuclidean, manhattan, chebyshev, minkowski(p) or cosine
+    #[arg(long, default_value = "euclidean")]
+    metric: DistanceMetric,
+    #[arg(long, default_value_t = 300)]
+    max_iter: usize,
+    /// Random seed, for reproducible labels
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Save the fitted model, in the binary format for a `.bin` file and as JSON otherwise
+    #[arg(long)]
+    save: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct PredictArgs {
+    #[command(flatten)]
+    input: InputArgs,
+    /// Model saved by `fit --save`
+    #[arg(long)]
+    model: PathBuf,
+}
+
+/// The rows of a CSV/TSV file, kept as text to write them back with `--append`.
+struct Table {
+    delimiter: u8,
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    /// Line number of each row in the input, for the error messages
+    lines: Vec<u64>,
+}
+
+impl Table {
+    /// Read the non-blank rows, splitting them on `delimiter` outside of quoted fields.
+    fn read(reader: impl BufRead, delimiter: char, header: bool) -> Result<Self, Box<dyn Error>> {
+        if !delimiter.is_ascii() {
+            return Err(format!("the delimiter {delimiter:?} is not an ASCII character").into());
+        }
+        let delimiter = delimiter as u8;
+        let mut rows = Vec::new();
+        let mut lines = Vec::new();
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        for record in reader.records() {
+            let record = record?;
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            rows.push(record.iter().map(str::to_string).collect());
+            lines.push(record.position().map_or(0, csv::Position::line));
+        }
+        let header = if header && !rows.is_empty() {
+            lines.remove(0);
+            Some(rows.remove(0))
+        } else {
+            None
+        };
+        Ok(Table {
+            delimiter,
+            header,
+            rows,
+            lines,
+        })
+    }
+
+    /// Parse the values of the given columns, all the columns when none is given.
+    fn points(&self, columns: &[String]) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+        let indices = columns
+            .iter()
+            .map(|column| self.column_index(column))
+            .collect::<Result<Vec<usize>, _>>()?;
+        self.rows
+            .iter()
+            .zip(&self.lines)
+            .map(|(fields, line)| {
+                let parse = |column: usize| -> Result<f64, Box<dyn Error>> {
+                    let field = fields
+                        .get(column)
+                        .ok_or_else(|| format!("line {line}: no column {column}"))?;
+                    Ok(field.parse().map_err(|_| {
+                        format!("line {line}, column {column}: {field:?} is not a number")
+                    })?)
+                };
+                if indices.is_empty() {
+                    (0..fields.len()).map(parse).collect()
+                } else {
+                    indices.iter().map(|&column| parse(column)).collect()
+                }
+            })
+            .collect()
+    }
+
+    fn column_index(&self, column: &str) -> Result<usize, Box<dyn Error>> {
+        if let Some(index) = self
+            .header
+            .as_ref()
+            .and_then(|header| header.iter().position(|name| name == column))
+        {
+            return Ok(index);
+        }
+        column
+            .parse()
+            .map_err(|_| format!("unknown column {column:?}").into())
+    }
+
+    /// Write the labels one per line, or the rows with an appended label column.
+    fn write_labels(
+        &self,
+        labels: &[usize],
+        append: bool,
+        out: &mut impl Write,
+    ) -> Result<(), Box<dyn Error>> {
+        if !append {
+            for label in labels {
+                writeln!(out, "{label}")?;
+            }
+            return Ok(());
+        }
+        // Quote the fields holding the delimiter back
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .flexible(true)
+            .from_writer(out);
+        if let Some(header) = &self.header {
+            writer.write_record(header.iter().map(String::as_str).chain(["label"]))?;
+        }
+        for (fields, label) in self.rows.iter().zip(labels) {
+            let label = label.to_string();
+            writer.write_record(fields.iter().chain([&label]))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl InputArgs {
+    fn read_table(&self, stdin: impl BufRead) -> Result<Table, Box<dyn Error>> {
+        let delimiter = self.delimiter.unwrap_or(match &self.input {
+            Some(path) if path.extension().is_some_and(|extension| extension == "tsv") => '\t',
+            _ => ',',
+        });
+        match &self.input {
+            Some(path) if path != Path::new("-") => {
+                let file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+                Table::read(BufReader::new(file), delimiter, self.header)
+            }
+            _ => Table::read(stdin, delimiter, self.header),
+        }
+    }
+}
+
+fn is_binary(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "bin")
+}
+
+fn save_model(kmeans: &Kmeans, path: &Path) -> Result<(), Box<dyn Error>> {
+    let bytes = if is_binary(path) {
+        kmeans.to_bytes()?
+    } else {
+        kmeans.to_json()?.into_bytes()
+    };
+    std::fs::write(path, bytes).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(())
+}
+
+fn load_model(path: &Path) -> Result<Kmeans, Box<dyn Error>> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    if is_binary(path) {
+        Ok(Kmeans::from_bytes(&bytes)?)
+    } else {
+        Ok(Kmeans::from_json(std::str::from_utf8(&bytes)?)?)
+    }
+}
+
+fn run(cli: Cli, stdin: impl BufRead, stdout: impl Write) -> Result<(), Box<dyn Error>> {
+    let mut out = BufWriter::new(stdout);
+    match cli.command {
+        Command::Fit(args) => {
+            let table = args.input.read_table(stdin)?;
+            let mut kmeans = Kmeans::new(args.k, args.max_iter, args.init, args.metric, args.seed);
+            kmeans.fit(table.points(&args.input.columns)?)?;
+            if let Some(path) = &args.save {
+                save_model(&kmeans, path)?;
+            }
+            table.write_labels(&kmeans.get_labels().0, args.input.append, &mut out)?;
+        }
+        Command::Predict(args) => {
+            let kmeans = load_model(&args.model)?;
+            let table = args.input.read_table(stdin)?;
+            let labels = kmeans.predict(table.points(&args.input.columns)?)?;
+            table.write_labels(&labels.0, args.input.append, &mut out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse(), io::stdin().lock(), io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    const POINTS: &str = "name,x,y\na,1,0\nb,1,1\nc,1,2\nd,10,0\ne,10,1\nf,10,2\n";
+
+    fn run_args(args: &[&str], input: &str) -> Result<String, Box<dyn Error>> {
+        let cli =
+            Cli::try_parse_from(std::iter::once("toymlrs-clustering").chain(args.iter().copied()))?;
+        let mut out = Vec::new();
+        run(cli, input.as_bytes(), &mut out)?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_fit() {
+        let args = [
+            "fit",
+            "--k",
+            "2",
+            "--seed",
+            "42",
+            "--header",
+            "--columns",
+            "x,y",
+        ];
+        let labels = run_args(&args, POINTS).unwrap();
+        let labels: Vec<&str> = labels.lines().collect();
+        assert_eq!(labels.len(), 6);
+        assert!(labels[..3].iter().all(|&label| label == labels[0]));
+        assert!(labels[3..].iter().all(|&label| label == labels[3]));
+        assert_ne!(labels[0], labels[3]);
+
+        // Columns by index, tab-separated, with the label column appended
+        let tsv = POINTS.replace(',', "\t");
+        let args = [
+            "fit",
+            "--k",
+            "2",
+            "--seed",
+            "42",
+            "--header",
+            "--columns",
+            "1,2",
+            "--delimiter",
+            "\t",
+            "--append",
+            "--init",
+            "kmeans||",
+            "--metric",
+            "manhattan",
+        ];
+        let rows = run_args(&args, &tsv).unwrap();
+        let rows: Vec<&str> = rows.lines().collect();
+        assert_eq!(rows[0], "name\tx\ty\tlabel");
+        assert!(rows[1].starts_with("a\t1\t0\t"));
+        assert!(rows[6].starts_with("f\t10\t2\t"));
+        let appended: Vec<&str> = rows[1..]
+            .iter()
+            .map(|row| row.rsplit('\t').next().unwrap())
+            .collect();
+        assert!(appended[..3].iter().all(|&label| label == appended[0]));
+        assert!(appended[3..].iter().all(|&label| label == appended[3]));
+        assert_ne!(appended[0], appended[3]);
+    }
+
+    #[test]
+    fn test_cli_quoted_fields() {
+        let points = POINTS
+            .replace("name,x,y", "\"name\",\"x\",\"y\"")
+            .replace("a,", "\"a, first\",");
+        let args = [
+            "fit",
+            "--k",
+            "2",
+            "--seed",
+            "42",
+            "--header",
+            "--columns",
+            "x,y",
+            "--append",
+        ];
+        let rows = run_args(&args, &points).unwrap();
+        let rows: Vec<&str> = rows.lines().collect();
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[0], "name,x,y,label");
+        // The field holding the delimiter is quoted back
+        assert!(rows[1].starts_with("\"a, first\",1,0,"));
+    }
+
+    #[test]
+    fn test_cli_save_and_predict() {
+        for extension in ["json", "bin"] {
+            let path = std::env::temp_dir().join(format!(
+                "toymlrs-cli-model-{}.{extension}",
+                std::process::id()
+            ));
+            let model = path.to_str().unwrap();
+            let points = "1,0\n1,1\n1,2\n10,0\n10,1\n10,2\n";
+            let args = ["fit", "--k", "2", "--seed", "1", "--save", model];
+            let labels = run_args(&args, points).unwrap();
+            let predicted = run_args(&["predict", "--model", model], points).unwrap();
+            assert_eq!(predicted, labels);
+            let predicted = run_args(&["predict", "--model", model], "0,1\n11,1\n").unwrap();
+            let labels: Vec<&str> = labels.lines().collect();
+            assert_eq!(predicted, format!("{}\n{}\n", labels[0], labels[3]));
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_cli_errors() {
+        let error = |args: &[&str], input: &str| run_args(args, input).unwrap_err().to_string();
+        assert_eq!(
+            error(&["fit", "--k", "2", "--header", "--columns", "z"], POINTS),
+            "unknown column \"z\""
+        );
+        assert_eq!(
+            error(&["fit", "--k", "2", "--header"], POINTS),
+            "line 2, column 0: \"a\" is not a number"
+        );
+        assert_eq!(
+            error(&["fit", "--k", "2", "--columns", "3"], "1,2\n3,4\n"),
+            "line 1: no column 3"
+        );
+        assert_eq!(
+            error(&["fit", "--k", "3"], "1,2\n3,4\n"),
+            toymlrs_clustering::Error::InvalidK { k: 3, n_samples: 2 }.to_string()
+        );
+        // The blank lines are skipped but still counted
+        assert_eq!(
+            error(&["fit", "--k", "2", "--header"], "x,y\n1,2\n\n \n3,a\n"),
+            "line 5, column 1: \"a\" is not a number"
+        );
+        assert!(run_args(&["fit", "--k", "2", "--init", "foo"], "").is_err());
+        assert!(
+            run_args(&["predict", "--model", "/nonexistent/model.json"], "1,2\n")
+                .unwrap_err()
+                .to_string()
+                .starts_with("/nonexistent/model.json: ")
+        );
+    }
 }
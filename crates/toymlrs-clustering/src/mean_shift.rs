@@ -0,0 +1,462 @@
+use crate::dataset::{IntoPoints, Point, Points};
+use crate::distance::{Distance, DistanceMetric};
+use crate::kmeans::Centroids;
+use crate::neighbors::core_distances;
+use crate::{Error, Float};
+use parse_display::{Display, FromStr};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// How the points around a center weigh in its next position.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, FromStr)]
+pub enum Kernel {
+    /// The points within the bandwidth, equally weighted
+    #[default]
+    #[display("flat")]
+    Flat,
+    /// Every point, weighted by `exp(-(d / bandwidth)² / 2)`
+    #[display("gaussian")]
+    Gaussian,
+}
+
+/// Estimate a bandwidth for [`MeanShift`], the mean distance from each point to its
+/// `quantile * n_samples`-th nearest point, itself included.
+///
+/// The quantile is between 0 and 1, the higher the wider the bandwidth and the fewer clusters.
+pub fn estimate_bandwidth<'a, F: Float>(
+    points: impl IntoPoints<'a, F>,
+    quantile: F,
+    distance_metric: &impl Distance<F>,
+) -> Result<F, Error> {
+    if !(quantile > F::zero() && quantile <= F::one()) {
+        return Err(Error::InvalidParameter {
+            name: "quantile".to_string(),
+            reason: "must be in ]0, 1]".to_string(),
+        });
+    }
+    distance_metric.check()?;
+    let points = points.into_points()?;
+    points.check()?;
+    let n = points.len();
+    let n_neighbors = (quantile * F::from_f64(n as f64))
+        .to_usize()
+        .unwrap_or(n)
+        .clamp(1, n);
+    let distances = core_distances(&points, n_neighbors, distance_metric);
+    Ok(distances.into_iter().sum::<F>() / F::from_f64(n as f64))
+}
+
+/// Mean shift, moving seeds to the densest nearby region until they settle on the modes of the
+/// density of the points.
+///
+/// Each seed repeatedly moves to the kernel-weighted mean of the points around it. Seeds
+/// settling within a bandwidth of a mode reached by more points are merged into it, the
+/// remaining modes being the cluster centers, so the number of clusters is found from the data.
+#[derive(Debug)]
+pub struct MeanShift<F: Float = f64, D: Distance<F> = DistanceMetric> {
+    /// Radius of the kernel, estimated with [`estimate_bandwidth`] and a quantile of 0.3 if
+    /// `None`
+    pub bandwidth: Option<F>,
+    pub kernel: Kernel,
+    /// Seed from the points rounded to a grid of bandwidth-sized bins instead of every point,
+    /// much faster on large datasets
+    pub bin_seeding: bool,
+    /// Fewest points in a bin to seed from it
+    pub min_bin_freq: usize,
+    pub max_iter: usize,
+    pub distance_metric: D,
+    cluster_centers: Centroids<F>,
+    labels: Vec<usize>,
+    fitted_bandwidth: F,
+    n_iter: usize,
+}
+
+impl<F: Float> Default for MeanShift<F> {
+    fn default() -> Self {
+        MeanShift {
+            bandwidth: None,
+            kernel: Kernel::default(),
+            bin_seeding: false,
+            min_bin_freq: 1,
+            max_iter: 300,
+            distance_metric: DistanceMetric::default(),
+            cluster_centers: Centroids::default(),
+            labels: Vec::new(),
+            fitted_bandwidth: F::zero(),
+            n_iter: 0,
+        }
+    }
+}
+
+impl<F: Float> MeanShift<F> {
+    pub fn new(
+        bandwidth: Option<F>,
+        kernel: Kernel,
+        bin_seeding: bool,
+        distance_metric: DistanceMetric,
+    ) -> Self {
+        MeanShift {
+            bandwidth,
+            kernel,
+            bin_seeding,
+            distance_metric,
+            ..MeanShift::default()
+        }
+    }
+}
+
+impl<F: Float, D: Distance<F>> MeanShift<F, D> {
+    /// Use a custom distance between the points and the centers.
+    pub fn with_distance_metric<E: Distance<F>>(self, distance_metric: E) -> MeanShift<F, E> {
+        MeanShift {
+            bandwidth: self.bandwidth,
+            kernel: self.kernel,
+            bin_seeding: self.bin_seeding,
+            min_bin_freq: self.min_bin_freq,
+            max_iter: self.max_iter,
+            distance_metric,
+            cluster_centers: self.cluster_centers,
+            labels: self.labels,
+            fitted_bandwidth: self.fitted_bandwidth,
+            n_iter: self.n_iter,
+        }
+    }
+
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        let points = points.into_points()?;
+        points.check()?;
+        let bandwidth = match self.bandwidth {
+            Some(bandwidth) => bandwidth,
+            None => estimate_bandwidth(&points, F::from_f64(0.3), &self.distance_metric)?,
+        };
+        if !(bandwidth.is_finite() && bandwidth > F::zero()) {
+            return Err(Error::InvalidParameter {
+                name: "bandwidth".to_string(),
+                reason: format!(
+                    "must be positive and finite, got {bandwidth:?}{}",
+                    if self.bandwidth.is_none() {
+                        " from estimate_bandwidth"
+                    } else {
+                        ""
+                    }
+                ),
+            });
+        }
+        let seeds = if self.bin_seeding {
+            self.bin_seeds(&points, bandwidth)
+        } else {
+            points.rows().map(<[F]>::to_vec).collect()
+        };
+        #[cfg(feature = "parallel")]
+        let seeds_iter = seeds.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let seeds_iter = seeds.iter();
+        let modes: Vec<(Vec<F>, usize, usize)> = seeds_iter
+            .filter_map(|seed| self.shift(&points, seed.clone(), bandwidth))
+            .collect();
+        if modes.is_empty() {
+            return Err(Error::InvalidParameter {
+                name: "bandwidth".to_string(),
+                reason: "no seed has any point within the bandwidth".to_string(),
+            });
+        }
+        self.n_iter = modes
+            .iter()
+            .map(|&(_, _, n_iter)| n_iter)
+            .max()
+            .unwrap_or(0);
+
+        // The most popular modes first, each absorbing the others within the bandwidth
+        let mut order: Vec<usize> = (0..modes.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(modes[i].1));
+        let mut centers: Vec<&[F]> = Vec::new();
+        for i in order {
+            let mode = &modes[i].0;
+            if centers
+                .iter()
+                .all(|center| self.distance_metric.distance(center, mode) >= bandwidth)
+            {
+                centers.push(mode);
+            }
+        }
+        self.cluster_centers = Centroids {
+            centroid_map: centers
+                .into_iter()
+                .enumerate()
+                .map(|(index, center)| {
+                    (
+                        index,
+                        Point {
+                            values: center.to_vec(),
+                        },
+                    )
+                })
+                .collect::<HashMap<_, _>>(),
+        };
+        self.fitted_bandwidth = bandwidth;
+        self.labels = points
+            .rows()
+            .map(|point| {
+                self.cluster_centers
+                    .get_nearest_cluster_index(point, &self.distance_metric)
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Centers of the grid bins holding at least `min_bin_freq` points, or every point if
+    /// binning does not reduce the seeds.
+    fn bin_seeds(&self, points: &Points<F>, bandwidth: F) -> Vec<Vec<F>> {
+        let mut bins: HashMap<Vec<i64>, usize> = HashMap::new();
+        let mut bin_order = Vec::new();
+        for point in points.rows() {
+            let bin: Vec<i64> = point
+                .iter()
+                .map(|&x| (x / bandwidth).round().to_i64().unwrap_or_default())
+                .collect();
+            let count = bins.entry(bin.clone()).or_insert(0);
+            if *count == 0 {
+                bin_order.push(bin);
+            }
+            *count += 1;
+        }
+        let seeds: Vec<Vec<F>> = bin_order
+            .into_iter()
+            .filter(|bin| bins[bin] >= self.min_bin_freq)
+            .map(|bin| {
+                bin.into_iter()
+                    .map(|i| F::from_f64(i as f64) * bandwidth)
+                    .collect()
+            })
+            .collect();
+        if seeds.len() == points.len() {
+            points.rows().map(<[F]>::to_vec).collect()
+        } else {
+            seeds
+        }
+    }
+
+    /// Move `center` to the mean of the points around it until it settles, returning where,
+    /// how many points are within the bandwidth and the number of iterations.
+    fn shift(
+        &self,
+        points: &Points<F>,
+        mut center: Vec<F>,
+        bandwidth: F,
+    ) -> Option<(Vec<F>, usize, usize)> {
+        let stop_threshold = F::from_f64(1e-3) * bandwidth;
+        let mut n_iter = 0;
+        let mut n_within = 0;
+        while n_iter < self.max_iter {
+            n_iter += 1;
+            let mut total_weight = F::zero();
+            let mut mean = vec![F::zero(); center.len()];
+            n_within = 0;
+            for point in points.rows() {
+                let distance = self.distance_metric.distance(&center, point);
+                if distance <= bandwidth {
+                    n_within += 1;
+                }
+                let weight = match self.kernel {
+                    Kernel::Flat if distance <= bandwidth => F::one(),
+                    Kernel::Flat => continue,
+                    Kernel::Gaussian => {
+                        let scaled = distance / bandwidth;
+                        (F::from_f64(-0.5) * scaled * scaled).exp()
+                    }
+                };
+                total_weight += weight;
+                for (m, &x) in mean.iter_mut().zip(point) {
+                    *m += weight * x;
+                }
+            }
+            if n_within == 0 || total_weight == F::zero() {
+                break;
+            }
+            mean.iter_mut().for_each(|m| *m /= total_weight);
+            let shift = self.distance_metric.distance(&mean, &center);
+            center = mean;
+            if shift <= stop_threshold {
+                break;
+            }
+        }
+        (n_within > 0).then_some((center, n_within, n_iter))
+    }
+
+    pub fn fit_predict<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<&[usize], Error> {
+        self.fit(points)?;
+        Ok(&self.labels)
+    }
+
+    /// Assign each point to the nearest cluster center of the fitted model.
+    pub fn predict<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Vec<usize>, Error> {
+        let expected = self.cluster_centers.dim().ok_or(Error::NotFitted)?;
+        let points = points.into_points()?;
+        let found = points.check()?;
+        if found != expected {
+            return Err(Error::DimensionMismatch { expected, found });
+        }
+        Ok(points
+            .rows()
+            .map(|point| {
+                self.cluster_centers
+                    .get_nearest_cluster_index(point, &self.distance_metric)
+            })
+            .collect())
+    }
+
+    /// The modes found by the last fit, the most popular first, keyed like
+    /// [`Centroids::centroid_map`].
+    pub fn get_cluster_centers(&self) -> &Centroids<F> {
+        &self.cluster_centers
+    }
+
+    /// The cluster of each point of the last fit, the key of its nearest center.
+    pub fn get_labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// The bandwidth used by the last fit, given or estimated.
+    pub fn get_bandwidth(&self) -> F {
+        self.fitted_bandwidth
+    }
+
+    /// Largest number of iterations a seed took to settle during the last fit.
+    pub fn get_n_iter(&self) -> usize {
+        self.n_iter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_points() -> Vec<Vec<f64>> {
+        let offsets = [
+            [0.0, 0.0],
+            [0.3, 0.1],
+            [-0.2, 0.3],
+            [0.1, -0.3],
+            [-0.3, -0.2],
+        ];
+        [[0.0, 0.0], [5.0, 5.0], [0.0, 5.0]]
+            .iter()
+            .flat_map(|center| {
+                offsets
+                    .iter()
+                    .map(|offset| vec![center[0] + offset[0], center[1] + offset[1]])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mean_shift_fit() {
+        for kernel in [Kernel::Flat, Kernel::Gaussian] {
+            for bin_seeding in [false, true] {
+                let mut mean_shift =
+                    MeanShift::new(Some(1.5), kernel, bin_seeding, DistanceMetric::Euclidean);
+                let labels = mean_shift
+                    .fit_predict(create_test_points())
+                    .unwrap()
+                    .to_vec();
+                let centers = &mean_shift.get_cluster_centers().centroid_map;
+                assert_eq!(centers.len(), 3, "{kernel} {bin_seeding}");
+                assert!(
+                    (0..15).all(|i| (0..15).all(|j| (labels[i] == labels[j]) == (i / 5 == j / 5))),
+                    "{kernel} {bin_seeding}"
+                );
+                for (i, expected) in [[-0.02, -0.02], [4.98, 4.98], [-0.02, 4.98]]
+                    .iter()
+                    .enumerate()
+                {
+                    let center = &centers[&labels[5 * i]].values;
+                    assert!(
+                        center
+                            .iter()
+                            .zip(expected)
+                            .all(|(x, y)| (x - y).abs() < 0.1),
+                        "{kernel} {bin_seeding} {center:?}"
+                    );
+                }
+                assert_eq!(
+                    mean_shift
+                        .predict(vec![vec![4.0, 6.0], vec![1.0, -1.0]])
+                        .unwrap(),
+                    [labels[5], labels[0]]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mean_shift_estimated_bandwidth() {
+        let points = vec![vec![0.0], vec![1.0], vec![2.0], vec![3.0]];
+        assert_eq!(
+            estimate_bandwidth(points, 0.5, &DistanceMetric::Euclidean),
+            Ok(1.0)
+        );
+        let mut mean_shift = MeanShift::default();
+        mean_shift.fit(create_test_points()).unwrap();
+        assert!(mean_shift.get_bandwidth() > 0.0);
+        assert_eq!(mean_shift.get_cluster_centers().centroid_map.len(), 3);
+        assert!(mean_shift.get_n_iter() >= 1);
+    }
+
+    #[test]
+    fn test_mean_shift_f32() {
+        let values: Vec<f32> = create_test_points()
+            .concat()
+            .into_iter()
+            .map(|x| x as f32)
+            .collect();
+        let mut mean_shift =
+            MeanShift::new(Some(1.5), Kernel::Flat, true, DistanceMetric::Euclidean);
+        mean_shift.fit(Points::view(&values, 2).unwrap()).unwrap();
+        assert_eq!(mean_shift.get_cluster_centers().dim(), Some(2));
+        assert_eq!(mean_shift.get_cluster_centers().centroid_map.len(), 3);
+    }
+
+    #[test]
+    fn test_mean_shift_errors() {
+        let mut mean_shift = MeanShift::<f64>::default();
+        assert_eq!(
+            mean_shift.predict(create_test_points()),
+            Err(Error::NotFitted)
+        );
+        mean_shift.bandwidth = Some(0.0);
+        assert!(matches!(
+            mean_shift.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        assert!(matches!(
+            estimate_bandwidth(create_test_points(), 1.5, &DistanceMetric::Euclidean),
+            Err(Error::InvalidParameter { .. })
+        ));
+        mean_shift.bandwidth = None;
+        assert!(matches!(
+            mean_shift.fit(vec![vec![1.0, 1.0], vec![1.0, 1.0]]),
+            Err(Error::InvalidParameter { .. })
+        ));
+        assert_eq!(mean_shift.fit(vec![]), Err(Error::EmptyDataset));
+        mean_shift.fit(create_test_points()).unwrap();
+        assert_eq!(
+            mean_shift.predict(vec![vec![1.0]]),
+            Err(Error::DimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert!(matches!(
+            estimate_bandwidth(create_test_points(), 0.3, &DistanceMetric::Minkowski(0.0)),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        mean_shift.distance_metric = DistanceMetric::Minkowski(0.0);
+        assert!(matches!(
+            mean_shift.fit(create_test_points()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        assert_eq!("gaussian".parse(), Ok(Kernel::Gaussian));
+    }
+}
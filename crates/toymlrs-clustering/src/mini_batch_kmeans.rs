@@ -0,0 +1,416 @@
+pub use crate::kmeans::{Centroids, CentroidsInitMethod, Labels};
+use crate::{
+    dataset::{IntoPoints, Points},
+    distance::{Distance, DistanceMetric},
+    Error, Float,
+};
+use rand::prelude::SeedableRng;
+use rand::random;
+use rand::seq::index;
+use rand::Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Mini-batch k-means, moving the centroids with small random batches instead of the whole
+/// dataset at each iteration.
+///
+/// Each centroid has its own learning rate, the inverse of the number of points it has been
+/// assigned so far, so the centroids settle as they see more points.
+#[derive(Debug)]
+pub struct MiniBatchKmeans<F: Float = f64, D: Distance<F> = DistanceMetric> {
+    pub k: usize,
+    /// Number of points in each batch
+    pub batch_size: usize,
+    /// Maximum number of passes over the dataset in `fit`
+    pub max_iter: usize,
+    /// Stop once no centroid moves more than `tol` during a batch, `0` to always run `max_iter`
+    /// passes
+    pub tol: F,
+    pub centroids_init_method: CentroidsInitMethod,
+    pub random_seed: Option<u64>,
+    pub distance_metric: D,
+    centroids: Centroids<F>,
+    counts: Vec<usize>,
+    labels: Labels,
+    inertia: F,
+    n_steps: usize,
+}
+
+impl<F: Float> Default for MiniBatchKmeans<F> {
+    fn default() -> Self {
+        MiniBatchKmeans {
+            k: 2,
+            batch_size: 1024,
+            max_iter: 100,
+            tol: F::zero(),
+            centroids_init_method: CentroidsInitMethod::KmeansPlusPlus,
+            random_seed: None,
+            distance_metric: DistanceMetric::default(),
+            centroids: Centroids::default(),
+            counts: Vec::new(),
+            labels: Labels::default(),
+            inertia: F::zero(),
+            n_steps: 0,
+        }
+    }
+}
+
+impl<F: Float> MiniBatchKmeans<F> {
+    pub fn new(
+        k: usize,
+        batch_size: usize,
+        max_iter: usize,
+        centroids_init_method: CentroidsInitMethod,
+        distance_metric: DistanceMetric,
+        random_seed: Option<u64>,
+    ) -> Self {
+        MiniBatchKmeans {
+            k,
+            batch_size,
+            max_iter,
+            centroids_init_method,
+            distance_metric,
+            random_seed,
+            ..MiniBatchKmeans::default()
+        }
+    }
+}
+
+impl<F: Float, D: Distance<F>> MiniBatchKmeans<F, D> {
+    /// Use a custom distance to assign points to centroids.
+    pub fn with_distance_metric<E: Distance<F>>(self, distance_metric: E) -> MiniBatchKmeans<F, E> {
+        MiniBatchKmeans {
+            k: self.k,
+            batch_size: self.batch_size,
+            max_iter: self.max_iter,
+            tol: self.tol,
+            centroids_init_method: self.centroids_init_method,
+            random_seed: self.random_seed,
+            distance_metric,
+            centroids: self.centroids,
+            counts: self.counts,
+            labels: self.labels,
+            inertia: self.inertia,
+            n_steps: self.n_steps,
+        }
+    }
+
+    /// Fit the centroids with batches sampled from the points, then label every point.
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        if self.batch_size == 0 {
+            return Err(Error::InvalidParameter {
+                name: "batch_size".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        let points = &points.into_points()?;
+        points.check()?;
+        let mut rng =
+            rand::rngs::StdRng::seed_from_u64(self.random_seed.unwrap_or(random::<u64>()));
+        // Seed the centroids from a sample of a few batches, seeding from the whole dataset would
+        // cost as much as a Lloyd iteration
+        let init_size = (3 * self.batch_size).max(self.k).min(points.len());
+        let init_indices = index::sample(&mut rng, points.len(), init_size).into_vec();
+        let init_points = Points::new(
+            init_indices
+                .iter()
+                .flat_map(|&i| points.row(i).iter().copied())
+                .collect(),
+            points.dim(),
+        )?;
+        self.centroids = init_points.get_init_centroids(
+            self.centroids_init_method,
+            self.k,
+            Some(rng.gen()),
+            &self.distance_metric,
+        )?;
+        self.counts = vec![0; self.k];
+        self.n_steps = 0;
+
+        let batch_size = self.batch_size.min(points.len());
+        let max_steps = self
+            .max_iter
+            .saturating_mul(points.len())
+            .div_ceil(batch_size);
+        while self.n_steps < max_steps {
+            let batch: Vec<usize> = (0..batch_size)
+                .map(|_| rng.gen_range(0..points.len()))
+                .collect();
+            let old_centroids = self.centroids.clone();
+            self.fit_batch(points, &batch);
+            if self.centroids.get_max_shift(&old_centroids) <= self.tol {
+                break;
+            }
+        }
+        self.set_labels(points);
+        Ok(())
+    }
+
+    /// Update the centroids with one more chunk of points, used as a single batch.
+    ///
+    /// The first call seeds the centroids from the chunk, which needs at least `k` points. The
+    /// labels and inertia are the ones of the chunk.
+    pub fn partial_fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        let points = &points.into_points()?;
+        let dim = points.check()?;
+        match self.centroids.dim() {
+            None => {
+                self.centroids = points.get_init_centroids(
+                    self.centroids_init_method,
+                    self.k,
+                    self.random_seed,
+                    &self.distance_metric,
+                )?;
+                self.counts = vec![0; self.k];
+                self.n_steps = 0;
+            }
+            Some(expected) if expected != dim => {
+                return Err(Error::DimensionMismatch {
+                    expected,
+                    found: dim,
+                })
+            }
+            Some(_) => {}
+        }
+        let batch: Vec<usize> = (0..points.len()).collect();
+        self.fit_batch(points, &batch);
+        self.set_labels(points);
+        Ok(())
+    }
+
+    /// Move each centroid towards the points of the batch assigned to it.
+    fn fit_batch(&mut self, points: &Points<F>, batch: &[usize]) {
+        #[cfg(feature = "parallel")]
+        let batch_iter = batch.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let batch_iter = batch.iter();
+        // Assign the whole batch with the centroids from before the batch
+        let nearest_cluster_indices: Vec<usize> = batch_iter
+            .map(|&i| {
+                self.centroids
+                    .get_nearest_cluster_index(points.row(i), &self.distance_metric)
+            })
+            .collect();
+        for (&i, cluster_index) in batch.iter().zip(nearest_cluster_indices) {
+            self.counts[cluster_index] += 1;
+            let learning_rate = F::from_f64(self.counts[cluster_index] as f64).recip();
+            if let Some(centroid) = self.centroids.centroid_map.get_mut(&cluster_index) {
+                centroid
+                    .values
+                    .iter_mut()
+                    .zip(points.row(i))
+                    .for_each(|(c, &x)| *c = *c + learning_rate * (x - *c));
+            }
+        }
+        self.n_steps += 1;
+    }
+
+    /// Label the points with their nearest centroid, and sum their squared distances.
+    fn set_labels(&mut self, points: &Points<F>) {
+        self.labels = self.predict_points(points);
+        self.inertia = points
+            .rows()
+            .zip(&self.labels.0)
+            .map(|(point, cluster_index)| {
+                self.distance_metric
+                    .distance(&self.centroids.centroid_map[cluster_index].values, point)
+                    .powi(2)
+            })
+            .sum();
+    }
+
+    pub fn fit_predict<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<&Labels, Error> {
+        self.fit(points)?;
+        Ok(&self.labels)
+    }
+
+    /// Assign each point to the nearest centroid of the fitted model.
+    pub fn predict<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Labels, Error> {
+        let points = self.check_points(points)?;
+        Ok(self.predict_points(&points))
+    }
+
+    fn predict_points(&self, points: &Points<F>) -> Labels {
+        #[cfg(feature = "parallel")]
+        let points_iter = points.par_rows();
+        #[cfg(not(feature = "parallel"))]
+        let points_iter = points.rows();
+        Labels(
+            points_iter
+                .map(|point| {
+                    self.centroids
+                        .get_nearest_cluster_index(point, &self.distance_metric)
+                })
+                .collect(),
+        )
+    }
+
+    /// Distances from each point to every centroid of the fitted model.
+    ///
+    /// Row `i` holds the distances of the `i`-th point, column `j` the distance to centroid `j`.
+    pub fn transform<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Vec<Vec<F>>, Error> {
+        let points = self.check_points(points)?;
+        Ok(points
+            .rows()
+            .map(|point| self.centroids.get_distances(point, &self.distance_metric))
+            .collect())
+    }
+
+    fn check_points<'a>(&self, points: impl IntoPoints<'a, F>) -> Result<Points<'a, F>, Error> {
+        let expected = self.centroids.dim().ok_or(Error::NotFitted)?;
+        let points = points.into_points()?;
+        let found = points.check()?;
+        if found != expected {
+            return Err(Error::DimensionMismatch { expected, found });
+        }
+        Ok(points)
+    }
+
+    pub fn get_centroids(&self) -> &Centroids<F> {
+        &self.centroids
+    }
+
+    pub fn get_labels(&self) -> &Labels {
+        &self.labels
+    }
+
+    /// Within-cluster sum of squared distances of the points of the last `fit` or `partial_fit`.
+    pub fn get_inertia(&self) -> F {
+        self.inertia
+    }
+
+    /// Number of batches used to move the centroids, over every `partial_fit` call.
+    pub fn get_n_steps(&self) -> usize {
+        self.n_steps
+    }
+
+    /// Number of points assigned to each centroid so far, ordered by centroid index.
+    pub fn get_counts(&self) -> &[usize] {
+        &self.counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_points() -> Vec<Vec<f64>> {
+        (0..200)
+            .map(|i| {
+                let offset = if i % 2 == 0 { 0.0 } else { 10.0 };
+                vec![offset + (i % 5) as f64 * 0.1, offset - (i % 3) as f64 * 0.1]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mini_batch_kmeans_fit() {
+        let mut kmeans = MiniBatchKmeans::new(
+            2,
+            16,
+            10,
+            CentroidsInitMethod::KmeansPlusPlus,
+            DistanceMetric::Euclidean,
+            Some(42),
+        );
+        let labels = kmeans.fit_predict(create_test_points()).unwrap().0.clone();
+        assert_eq!(labels.len(), 200);
+        for (i, &label) in labels.iter().enumerate() {
+            assert_eq!(label, labels[i % 2]);
+        }
+        assert_ne!(labels[0], labels[1]);
+        assert_eq!(kmeans.get_n_steps(), 125);
+        assert_eq!(kmeans.get_counts().iter().sum::<usize>(), 125 * 16);
+        assert!(kmeans.get_inertia() < 6.0);
+        let centroid = &kmeans.get_centroids().centroid_map[&labels[1]].values;
+        assert!((centroid[0] - 10.2).abs() < 0.1);
+        assert!((centroid[1] - 9.9).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_mini_batch_kmeans_reproducible() {
+        let fit = || {
+            let mut kmeans = MiniBatchKmeans {
+                k: 3,
+                batch_size: 8,
+                random_seed: Some(7),
+                ..Default::default()
+            };
+            kmeans.fit(create_test_points()).unwrap();
+            kmeans
+        };
+        let (first, second) = (fit(), fit());
+        assert_eq!(first.get_labels().0, second.get_labels().0);
+        for (index, centroid) in &first.get_centroids().centroid_map {
+            assert_eq!(
+                centroid.values,
+                second.get_centroids().centroid_map[index].values
+            );
+        }
+    }
+
+    #[test]
+    fn test_mini_batch_kmeans_partial_fit() {
+        let points = create_test_points();
+        let mut kmeans: MiniBatchKmeans = MiniBatchKmeans {
+            random_seed: Some(42),
+            ..Default::default()
+        };
+        for chunk in points.chunks(50) {
+            kmeans.partial_fit(chunk).unwrap();
+        }
+        assert_eq!(kmeans.get_n_steps(), 4);
+        assert_eq!(kmeans.get_counts().iter().sum::<usize>(), 200);
+        assert_eq!(kmeans.get_labels().0.len(), 50);
+
+        let labels = kmeans.predict(points.as_slice()).unwrap().0;
+        assert_ne!(labels[0], labels[1]);
+        for (i, &label) in labels.iter().enumerate() {
+            assert_eq!(label, labels[i % 2]);
+        }
+        assert_eq!(
+            kmeans.partial_fit(vec![vec![1.0, 2.0, 3.0]]),
+            Err(Error::DimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_mini_batch_kmeans_errors() {
+        let mut kmeans: MiniBatchKmeans = MiniBatchKmeans {
+            batch_size: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            kmeans.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        assert_eq!(
+            kmeans.predict(create_test_points()).unwrap_err(),
+            Error::NotFitted
+        );
+        let mut kmeans: MiniBatchKmeans = MiniBatchKmeans {
+            k: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            kmeans.partial_fit(vec![vec![1.0], vec![2.0]]),
+            Err(Error::InvalidK { k: 3, n_samples: 2 })
+        );
+        for p in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            kmeans.distance_metric = DistanceMetric::Minkowski(p);
+            assert!(matches!(
+                kmeans.fit(create_test_points()),
+                Err(Error::InvalidParameter { name, .. }) if name == "p"
+            ));
+            assert!(matches!(
+                kmeans.partial_fit(create_test_points()),
+                Err(Error::InvalidParameter { name, .. }) if name == "p"
+            ));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Brute-force neighbour queries shared by the density-based algorithms.
+
+use crate::dataset::Points;
+use crate::distance::Distance;
+use crate::Float;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+/// Indices of the points within `eps` of each point, itself included, in increasing order.
+pub(crate) fn radius_neighbors<F: Float>(
+    points: &Points<F>,
+    eps: F,
+    distance_metric: &impl Distance<F>,
+) -> Vec<Vec<usize>> {
+    #[cfg(feature = "parallel")]
+    let rows = points.par_rows();
+    #[cfg(not(feature = "parallel"))]
+    let rows = points.rows();
+    rows.map(|point| {
+        points
+            .rows()
+            .enumerate()
+            .filter(|(_, other)| distance_metric.distance(point, other) <= eps)
+            .map(|(index, _)| index)
+            .collect()
+    })
+    .collect()
+}
+
+/// Distance from each point to its `k`-th nearest point, itself counting as the first.
+pub(crate) fn core_distances<F: Float>(
+    points: &Points<F>,
+    k: usize,
+    distance_metric: &impl Distance<F>,
+) -> Vec<F> {
+    #[cfg(feature = "parallel")]
+    let rows = points.par_rows();
+    #[cfg(not(feature = "parallel"))]
+    let rows = points.rows();
+    rows.map(|point| {
+        let mut distances: Vec<F> = points
+            .rows()
+            .map(|other| distance_metric.distance(point, other))
+            .collect();
+        let (_, &mut distance, _) = distances
+            .select_nth_unstable_by(k - 1, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        distance
+    })
+    .collect()
+}
@@ -0,0 +1,759 @@
+use crate::dataset::IntoPoints;
+use crate::distance::{Distance, DistanceMetric};
+use crate::neighbors::core_distances;
+use crate::{Error, Float};
+use parse_display::{Display, FromStr};
+
+/// How the clusters are read from the reachability plot when fitting.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, FromStr)]
+pub enum ClusterMethod {
+    /// Clusters start at a steep drop and end at a steep rise of the reachability, by at least
+    /// a relative `xi`, and nest into a hierarchy
+    #[default]
+    #[display("xi")]
+    Xi,
+    /// The clusters DBSCAN would find with `eps`, the reachability plot cut at `eps`
+    #[display("dbscan")]
+    Dbscan,
+}
+
+/// The clusters found by the Xi method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XiClusters {
+    /// The cluster of each point, `None` for noise, the smallest clusters of the hierarchy
+    /// being labelled first and the points of a larger cluster outside them left as noise
+    pub labels: Vec<Option<usize>>,
+    /// Every cluster found, as an inclusive `(start, end)` range of positions in the ordering,
+    /// each cluster before the clusters containing it
+    pub hierarchy: Vec<(usize, usize)>,
+}
+
+/// OPTICS, ordering the points so that the clusters of any density show as valleys in the plot
+/// of their reachability distances.
+///
+/// Each point is visited after the closest point, in reachability distance, of the points
+/// visited so far. The reachability of a point is its distance to the point it was reached
+/// from, at least the core distance of that point, i.e. its distance to its `min_samples`-th
+/// nearest point. Clusters are then extracted from the plot without refitting, with
+/// [`Optics::extract_dbscan`] for any `eps` or with [`Optics::extract_xi`].
+#[derive(Debug)]
+pub struct Optics<F: Float = f64, D: Distance<F> = DistanceMetric> {
+    /// Number of neighbours, the point itself included, making a point a core point
+    pub min_samples: usize,
+    /// Largest distance between two points for them to be neighbours, infinite to consider
+    /// every pair of points
+    pub max_eps: F,
+    pub cluster_method: ClusterMethod,
+    /// Reachability threshold of the DBSCAN extraction, `max_eps` if `None`
+    pub eps: Option<F>,
+    /// Smallest relative change of the reachability making a steep area for the Xi extraction,
+    /// between 0 and 1
+    pub xi: F,
+    /// Fewest points in a cluster of the Xi extraction, `min_samples` if `None`
+    pub min_cluster_size: Option<usize>,
+    pub distance_metric: D,
+    ordering: Vec<usize>,
+    reachability: Vec<F>,
+    core_distances: Vec<F>,
+    predecessor: Vec<Option<usize>>,
+    labels: Vec<Option<usize>>,
+    cluster_hierarchy: Vec<(usize, usize)>,
+    n_clusters: usize,
+}
+
+impl<F: Float> Default for Optics<F> {
+    fn default() -> Self {
+        Optics {
+            min_samples: 5,
+            max_eps: F::infinity(),
+            cluster_method: ClusterMethod::default(),
+            eps: None,
+            xi: F::from_f64(0.05),
+            min_cluster_size: None,
+            distance_metric: DistanceMetric::default(),
+            ordering: Vec::new(),
+            reachability: Vec::new(),
+            core_distances: Vec::new(),
+            predecessor: Vec::new(),
+            labels: Vec::new(),
+            cluster_hierarchy: Vec::new(),
+            n_clusters: 0,
+        }
+    }
+}
+
+impl<F: Float> Optics<F> {
+    pub fn new(
+        min_samples: usize,
+        max_eps: F,
+        cluster_method: ClusterMethod,
+        distance_metric: DistanceMetric,
+    ) -> Self {
+        Optics {
+            min_samples,
+            max_eps,
+            cluster_method,
+            distance_metric,
+            ..Optics::default()
+        }
+    }
+}
+
+impl<F: Float, D: Distance<F>> Optics<F, D> {
+    /// Use a custom distance to find the neighbours.
+    pub fn with_distance_metric<E: Distance<F>>(self, distance_metric: E) -> Optics<F, E> {
+        Optics {
+            min_samples: self.min_samples,
+            max_eps: self.max_eps,
+            cluster_method: self.cluster_method,
+            eps: self.eps,
+            xi: self.xi,
+            min_cluster_size: self.min_cluster_size,
+            distance_metric,
+            ordering: self.ordering,
+            reachability: self.reachability,
+            core_distances: self.core_distances,
+            predecessor: self.predecessor,
+            labels: self.labels,
+            cluster_hierarchy: self.cluster_hierarchy,
+            n_clusters: self.n_clusters,
+        }
+    }
+
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        if self.min_samples == 0 {
+            return Err(Error::InvalidParameter {
+                name: "min_samples".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        if self.max_eps.is_nan() || self.max_eps <= F::zero() {
+            return Err(Error::InvalidParameter {
+                name: "max_eps".to_string(),
+                reason: "must be positive".to_string(),
+            });
+        }
+        // Check the extraction parameters before the costly ordering
+        let eps = match self.cluster_method {
+            ClusterMethod::Dbscan => Some(self.check_eps(self.eps.unwrap_or(self.max_eps))?),
+            ClusterMethod::Xi => {
+                check_xi(self.xi)?;
+                None
+            }
+        };
+        let min_cluster_size = self.min_cluster_size.unwrap_or(self.min_samples);
+        let points = points.into_points()?;
+        points.check()?;
+        let n = points.len();
+        if self.min_samples > n {
+            return Err(Error::InvalidParameter {
+                name: "min_samples".to_string(),
+                reason: format!(
+                    "must be at most the number of points {n}, got {}",
+                    self.min_samples
+                ),
+            });
+        }
+
+        self.core_distances = core_distances(&points, self.min_samples, &self.distance_metric)
+            .into_iter()
+            .map(|d| if d <= self.max_eps { d } else { F::infinity() })
+            .collect();
+        self.reachability = vec![F::infinity(); n];
+        self.predecessor = vec![None; n];
+        self.ordering = Vec::with_capacity(n);
+        let mut processed = vec![false; n];
+        for _ in 0..n {
+            // The unprocessed point with the smallest reachability, the first one on ties
+            let point = (0..n)
+                .filter(|&i| !processed[i])
+                .fold(None, |best: Option<usize>, i| match best {
+                    Some(best) if self.reachability[best] <= self.reachability[i] => Some(best),
+                    _ => Some(i),
+                })
+                .unwrap_or_default();
+            processed[point] = true;
+            self.ordering.push(point);
+            let core_distance = self.core_distances[point];
+            if core_distance.is_infinite() {
+                continue;
+            }
+            let row = points.row(point);
+            for (other, values) in points.rows().enumerate() {
+                if processed[other] {
+                    continue;
+                }
+                let distance = self.distance_metric.distance(row, values);
+                if distance > self.max_eps {
+                    continue;
+                }
+                let reachability = core_distance.max(distance);
+                if reachability < self.reachability[other] {
+                    self.reachability[other] = reachability;
+                    self.predecessor[other] = Some(point);
+                }
+            }
+        }
+
+        match eps {
+            Some(eps) => {
+                self.labels = self.dbscan_labels(eps);
+                self.cluster_hierarchy = Vec::new();
+            }
+            None => {
+                let clusters = self.xi_labels(self.xi, min_cluster_size);
+                self.labels = clusters.labels;
+                self.cluster_hierarchy = clusters.hierarchy;
+            }
+        }
+        self.n_clusters = self
+            .labels
+            .iter()
+            .flatten()
+            .max()
+            .map_or(0, |&label| label + 1);
+        Ok(())
+    }
+
+    pub fn fit_predict<'a>(
+        &mut self,
+        points: impl IntoPoints<'a, F>,
+    ) -> Result<&[Option<usize>], Error> {
+        self.fit(points)?;
+        Ok(&self.labels)
+    }
+
+    fn check_eps(&self, eps: F) -> Result<F, Error> {
+        if !(eps.is_finite() && eps > F::zero() && eps <= self.max_eps) {
+            return Err(Error::InvalidParameter {
+                name: "eps".to_string(),
+                reason: format!(
+                    "must be positive, finite and at most max_eps {:?}, got {eps:?}",
+                    self.max_eps
+                ),
+            });
+        }
+        Ok(eps)
+    }
+
+    fn check_fitted(&self) -> Result<(), Error> {
+        if self.ordering.is_empty() {
+            return Err(Error::NotFitted);
+        }
+        Ok(())
+    }
+
+    /// The clusters of the last fit as DBSCAN would find them with `eps`, `None` for noise.
+    ///
+    /// The core points match those of DBSCAN, a border point near several clusters possibly
+    /// joining another one.
+    pub fn extract_dbscan(&self, eps: F) -> Result<Vec<Option<usize>>, Error> {
+        self.check_fitted()?;
+        let eps = self.check_eps(eps)?;
+        Ok(self.dbscan_labels(eps))
+    }
+
+    fn dbscan_labels(&self, eps: F) -> Vec<Option<usize>> {
+        let mut labels = vec![None; self.ordering.len()];
+        let mut current: Option<usize> = None;
+        for &point in &self.ordering {
+            if self.reachability[point] > eps {
+                // Not reachable from the current cluster, the start of a new one if core
+                if self.core_distances[point] <= eps {
+                    current = Some(current.map_or(0, |label| label + 1));
+                    labels[point] = current;
+                }
+            } else {
+                labels[point] = current;
+            }
+        }
+        labels
+    }
+
+    /// The clusters of the last fit found by the Xi method with another `xi` or
+    /// `min_cluster_size`.
+    pub fn extract_xi(&self, xi: F, min_cluster_size: usize) -> Result<XiClusters, Error> {
+        self.check_fitted()?;
+        check_xi(xi)?;
+        Ok(self.xi_labels(xi, min_cluster_size))
+    }
+
+    fn xi_labels(&self, xi: F, min_cluster_size: usize) -> XiClusters {
+        let reachability_plot: Vec<F> = self
+            .ordering
+            .iter()
+            .map(|&point| self.reachability[point])
+            .collect();
+        let predecessor_plot: Vec<Option<usize>> = self
+            .ordering
+            .iter()
+            .map(|&point| self.predecessor[point])
+            .collect();
+        let clusters = xi_clusters(
+            &reachability_plot,
+            &predecessor_plot,
+            &self.ordering,
+            xi,
+            self.min_samples,
+            min_cluster_size,
+        );
+
+        // Label the clusters not overlapping one already labelled, the smallest first
+        let mut plot_labels: Vec<Option<usize>> = vec![None; self.ordering.len()];
+        let mut label = 0;
+        for &(start, end) in &clusters {
+            if plot_labels[start..=end].iter().all(Option::is_none) {
+                plot_labels[start..=end].fill(Some(label));
+                label += 1;
+            }
+        }
+        let mut labels = vec![None; self.ordering.len()];
+        for (&point, plot_label) in self.ordering.iter().zip(plot_labels) {
+            labels[point] = plot_label;
+        }
+        XiClusters {
+            labels,
+            hierarchy: clusters,
+        }
+    }
+
+    /// The points of the last fit in the order they were visited.
+    pub fn get_ordering(&self) -> &[usize] {
+        &self.ordering
+    }
+
+    /// The reachability distance of each point, indexed by point, infinite for the first point
+    /// visited of each group of points more than `max_eps` away from the others.
+    ///
+    /// The reachability plot is this distance for the points taken in the ordering.
+    pub fn get_reachability(&self) -> &[F] {
+        &self.reachability
+    }
+
+    /// The distance from each point to its `min_samples`-th nearest point, infinite when it is
+    /// further than `max_eps`.
+    pub fn get_core_distances(&self) -> &[F] {
+        &self.core_distances
+    }
+
+    /// The point each point was reached from, `None` if unreachable.
+    pub fn get_predecessor(&self) -> &[Option<usize>] {
+        &self.predecessor
+    }
+
+    /// The cluster of each point of the last fit, `None` for noise.
+    pub fn get_labels(&self) -> &[Option<usize>] {
+        &self.labels
+    }
+
+    /// The clusters found by the Xi method in the last fit, as inclusive `(start, end)` ranges
+    /// of positions in the ordering, empty with the DBSCAN method.
+    pub fn get_cluster_hierarchy(&self) -> &[(usize, usize)] {
+        &self.cluster_hierarchy
+    }
+
+    pub fn get_n_clusters(&self) -> usize {
+        self.n_clusters
+    }
+}
+
+fn check_xi<F: Float>(xi: F) -> Result<(), Error> {
+    if !(xi > F::zero() && xi < F::one()) {
+        return Err(Error::InvalidParameter {
+            name: "xi".to_string(),
+            reason: format!("must be in ]0, 1[, got {xi:?}"),
+        });
+    }
+    Ok(())
+}
+
+/// A steep downward area of the reachability plot, a candidate start of clusters.
+struct SteepDownArea<F> {
+    start: usize,
+    end: usize,
+    /// The largest reachability between the end of the area and the current position
+    mib: F,
+}
+
+/// The end of the steep area starting at `start`, extended until a point going the opposite
+/// way or more than `min_samples` consecutive points which are not steep.
+fn extend_region(steep: &[bool], opposite: &[bool], start: usize, min_samples: usize) -> usize {
+    let mut non_steep = 0;
+    let mut end = start;
+    for index in start..steep.len() {
+        if steep[index] {
+            non_steep = 0;
+            end = index;
+        } else if opposite[index] {
+            break;
+        } else {
+            non_steep += 1;
+            if non_steep > min_samples {
+                break;
+            }
+        }
+    }
+    end
+}
+
+/// Keep the steep downward areas starting high enough above `mib` and update their `mib`.
+fn filter_steep_down_areas<F: Float>(
+    areas: Vec<SteepDownArea<F>>,
+    mib: F,
+    xi_complement: F,
+    reachability_plot: &[F],
+) -> Vec<SteepDownArea<F>> {
+    if mib.is_infinite() {
+        return Vec::new();
+    }
+    areas
+        .into_iter()
+        .filter(|area| mib <= reachability_plot[area.start] * xi_complement)
+        .map(|area| SteepDownArea {
+            mib: area.mib.max(mib),
+            ..area
+        })
+        .collect()
+}
+
+/// Shrink the cluster `start..=end` from the end until its last point was reached from a point
+/// of the cluster, as in Schubert and Gertz, "Improving the Cluster Structure Extracted from
+/// OPTICS Plots".
+fn correct_predecessor<F: Float>(
+    reachability_plot: &[F],
+    predecessor_plot: &[Option<usize>],
+    ordering: &[usize],
+    start: usize,
+    mut end: usize,
+) -> Option<(usize, usize)> {
+    while start < end {
+        if reachability_plot[start] > reachability_plot[end] {
+            return Some((start, end));
+        }
+        if let Some(predecessor) = predecessor_plot[end] {
+            if ordering[start..end].contains(&predecessor) {
+                return Some((start, end));
+            }
+        }
+        end -= 1;
+    }
+    None
+}
+
+/// The clusters of the reachability plot, as inclusive ranges of positions in the ordering,
+/// following Ankerst et al., "OPTICS: Ordering Points To Identify the Clustering Structure",
+/// with the corrections made by scikit-learn.
+fn xi_clusters<F: Float>(
+    reachability_plot: &[F],
+    predecessor_plot: &[Option<usize>],
+    ordering: &[usize],
+    xi: F,
+    min_samples: usize,
+    min_cluster_size: usize,
+) -> Vec<(usize, usize)> {
+    // An infinite reachability at the end closes the clusters reaching the last point
+    let mut plot = reachability_plot.to_vec();
+    plot.push(F::infinity());
+    let n = reachability_plot.len();
+    let xi_complement = F::one() - xi;
+    // NaN ratios, between two infinite reachabilities, are neither steep, upward nor downward
+    let ratios: Vec<F> = (0..n).map(|i| plot[i] / plot[i + 1]).collect();
+    let steep_upward: Vec<bool> = ratios.iter().map(|&r| r <= xi_complement).collect();
+    let steep_downward: Vec<bool> = ratios.iter().map(|&r| r >= xi_complement.recip()).collect();
+    let upward: Vec<bool> = ratios.iter().map(|&r| r < F::one()).collect();
+    let downward: Vec<bool> = ratios.iter().map(|&r| r > F::one()).collect();
+
+    let mut areas: Vec<SteepDownArea<F>> = Vec::new();
+    let mut clusters = Vec::new();
+    let mut index = 0;
+    let mut mib = F::zero();
+    for steep_index in (0..n).filter(|&i| steep_upward[i] || steep_downward[i]) {
+        // Already part of a steep area
+        if steep_index < index {
+            continue;
+        }
+        mib = plot[index..=steep_index]
+            .iter()
+            .fold(mib, |mib, &r| mib.max(r));
+        areas = filter_steep_down_areas(areas, mib, xi_complement, &plot);
+        if steep_downward[steep_index] {
+            let end = extend_region(&steep_downward, &upward, steep_index, min_samples);
+            areas.push(SteepDownArea {
+                start: steep_index,
+                end,
+                mib: F::zero(),
+            });
+            index = end + 1;
+            mib = plot[index];
+            continue;
+        }
+
+        let up_start = steep_index;
+        let up_end = extend_region(&steep_upward, &downward, up_start, min_samples);
+        index = up_end + 1;
+        mib = plot[index];
+        let mut up_clusters = Vec::new();
+        for area in &areas {
+            let mut start = area.start;
+            let mut end = up_end;
+            if plot[end + 1] * xi_complement < area.mib {
+                continue;
+            }
+            // Both ends at about the same level
+            let down_max = plot[area.start];
+            if down_max * xi_complement >= plot[end + 1] {
+                while start < area.end && plot[start + 1] > plot[end + 1] {
+                    start += 1;
+                }
+            } else if plot[end + 1] * xi_complement >= down_max {
+                while end > up_start && plot[end - 1] > down_max {
+                    end -= 1;
+                }
+            }
+            let Some((start, end)) =
+                correct_predecessor(reachability_plot, predecessor_plot, ordering, start, end)
+            else {
+                continue;
+            };
+            if end - start + 1 < min_cluster_size || start > area.end || end < up_start {
+                continue;
+            }
+            up_clusters.push((start, end));
+        }
+        // The innermost clusters first
+        clusters.extend(up_clusters.into_iter().rev());
+    }
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::Points;
+    use crate::dbscan::Dbscan;
+    use rand::prelude::*;
+
+    /// A dense blob of 30 points, a sparse blob of 30 points and 4 noise points.
+    fn create_test_points() -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut blob = |center: [f64; 2], radius: f64, n: usize| -> Vec<Vec<f64>> {
+            (0..n)
+                .map(|_| {
+                    let r = radius * rng.gen::<f64>().sqrt();
+                    let angle = rng.gen_range(0.0..2.0 * std::f64::consts::PI);
+                    vec![center[0] + r * angle.cos(), center[1] + r * angle.sin()]
+                })
+                .collect()
+        };
+        let mut points = blob([0.0, 0.0], 0.5, 30);
+        points.extend(blob([10.0, 0.0], 2.0, 30));
+        points.extend([
+            vec![5.0, 8.0],
+            vec![-6.0, 6.0],
+            vec![15.0, -9.0],
+            vec![4.0, -7.0],
+        ]);
+        points
+    }
+
+    fn blob_of(i: usize) -> usize {
+        i / 30
+    }
+
+    /// Each cluster lies within a blob, each blob has a cluster and the noise points are noise.
+    fn assert_blobs(labels: &[Option<usize>]) {
+        for (i, label) in labels.iter().enumerate() {
+            if label.is_some() {
+                assert!(blob_of(i) < 2, "{labels:?}");
+                assert!(
+                    (0..60).all(|j| labels[j] != *label || blob_of(j) == blob_of(i)),
+                    "{labels:?}"
+                );
+            }
+        }
+        assert!(labels[..30].iter().any(Option::is_some), "{labels:?}");
+        assert!(labels[30..60].iter().any(Option::is_some), "{labels:?}");
+    }
+
+    #[test]
+    fn test_optics_ordering() {
+        let mut optics = Optics::new(
+            5,
+            f64::INFINITY,
+            ClusterMethod::Xi,
+            DistanceMetric::Euclidean,
+        );
+        optics.fit(create_test_points()).unwrap();
+        let ordering = optics.get_ordering();
+        let mut sorted = ordering.to_vec();
+        sorted.sort();
+        assert_eq!(sorted, (0..64).collect::<Vec<_>>());
+        assert_eq!(ordering[0], 0);
+        let reachability = optics.get_reachability();
+        assert!(reachability[ordering[0]].is_infinite());
+        assert!(ordering[1..].iter().all(|&i| reachability[i].is_finite()));
+        // The dense blob is visited first, its points being closer to each other
+        assert!(ordering[..30].iter().all(|&i| i < 30));
+        let core_distances = optics.get_core_distances();
+        for (i, predecessor) in optics.get_predecessor().iter().enumerate() {
+            if let Some(predecessor) = *predecessor {
+                let distance = DistanceMetric::Euclidean
+                    .distance(&create_test_points()[i], &create_test_points()[predecessor]);
+                assert_eq!(reachability[i], distance.max(core_distances[predecessor]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_optics_xi() {
+        let mut optics = Optics::new(
+            5,
+            f64::INFINITY,
+            ClusterMethod::Xi,
+            DistanceMetric::Euclidean,
+        );
+        assert_blobs(optics.fit_predict(create_test_points()).unwrap());
+        // The dense blob, visited first, and the sparse blob are clusters of the hierarchy
+        let hierarchy = optics.get_cluster_hierarchy();
+        assert!(hierarchy.contains(&(0, 29)), "{hierarchy:?}");
+        let ordering = optics.get_ordering();
+        assert!(hierarchy.iter().any(|&(start, end)| {
+            let in_blob = ordering[start..=end]
+                .iter()
+                .filter(|&&i| blob_of(i) == 1)
+                .count();
+            in_blob == 30 && end - start < 32
+        }));
+        assert_eq!(hierarchy.last(), Some(&(0, 63)));
+        let clusters = optics.extract_xi(0.05, 5).unwrap();
+        assert_eq!(clusters.labels, optics.get_labels());
+        assert_eq!(clusters.hierarchy, hierarchy);
+    }
+
+    #[test]
+    fn test_optics_dbscan_extraction() {
+        let mut optics = Optics::new(5, 5.0, ClusterMethod::Dbscan, DistanceMetric::Euclidean);
+        optics.eps = Some(1.5);
+        assert_blobs(optics.fit_predict(create_test_points()).unwrap());
+        assert_eq!(optics.get_n_clusters(), 2);
+        assert!(
+            optics
+                .get_labels()
+                .iter()
+                .filter(|label| label.is_some())
+                .count()
+                >= 55
+        );
+        assert!(optics.get_cluster_hierarchy().is_empty());
+
+        // A small eps keeps the dense blob only, without refitting
+        let labels = optics.extract_dbscan(0.3).unwrap();
+        assert!(labels[..30].iter().filter(|label| label.is_some()).count() > 20);
+        assert!(labels[30..].iter().all(Option::is_none));
+
+        // The same core points as DBSCAN
+        for eps in [0.3, 1.0, 1.5] {
+            let labels = optics.extract_dbscan(eps).unwrap();
+            let mut dbscan = Dbscan::new(eps, 5, DistanceMetric::Euclidean);
+            dbscan.fit(create_test_points()).unwrap();
+            let dbscan_labels = dbscan.get_labels();
+            let core = dbscan.get_core_sample_indices();
+            for &i in core {
+                assert!(labels[i].is_some());
+                assert!(core
+                    .iter()
+                    .all(|&j| (labels[i] == labels[j]) == (dbscan_labels[i] == dbscan_labels[j])));
+            }
+        }
+    }
+
+    #[test]
+    fn test_extend_region() {
+        let inf = f64::INFINITY;
+        let steep_areas = |plot: &[f64]| {
+            let ratios: Vec<f64> = plot.windows(2).map(|w| w[0] / w[1]).collect();
+            (
+                ratios.iter().map(|&r| r >= 1.0 / 0.9).collect::<Vec<_>>(),
+                ratios.iter().map(|&r| r <= 0.9).collect::<Vec<_>>(),
+                ratios.iter().map(|&r| r < 1.0).collect::<Vec<_>>(),
+                ratios.iter().map(|&r| r > 1.0).collect::<Vec<_>>(),
+            )
+        };
+        for (plot, expected) in [
+            (vec![10.0, 8.9, 8.8, 8.7, 7.0, 10.0], 3),
+            (vec![10.0, 8.9, 8.8, 8.7, 8.6, 7.0, 10.0], 0),
+            (vec![10.0, 8.9, 8.8, 8.7, 7.0, 6.0, inf], 4),
+        ] {
+            let (steep_downward, _, upward, _) = steep_areas(&plot);
+            assert_eq!(extend_region(&steep_downward, &upward, 0, 2), expected);
+        }
+        for (plot, expected) in [
+            (vec![1.0, 2.0, 2.1, 2.2, 4.0, 8.0, 8.0, inf], 6),
+            (vec![1.0, 2.0, 2.1, 2.2, 2.3, 4.0, 8.0, 8.0, inf], 0),
+            (vec![1.0, 2.0, 2.1, 2.0, inf], 0),
+            (vec![1.0, 2.0, 2.1, inf], 2),
+        ] {
+            let (_, steep_upward, _, downward) = steep_areas(&plot);
+            assert_eq!(extend_region(&steep_upward, &downward, 0, 2), expected);
+        }
+    }
+
+    #[test]
+    fn test_optics_f32() {
+        let values: Vec<f32> = create_test_points()
+            .concat()
+            .into_iter()
+            .map(|x| x as f32)
+            .collect();
+        let mut optics = Optics::new(
+            5,
+            f32::INFINITY,
+            ClusterMethod::Xi,
+            DistanceMetric::Euclidean,
+        );
+        optics.fit(Points::view(&values, 2).unwrap()).unwrap();
+        assert_blobs(optics.get_labels());
+    }
+
+    #[test]
+    fn test_optics_errors() {
+        let mut optics = Optics::<f64>::default();
+        assert_eq!(optics.extract_dbscan(0.5), Err(Error::NotFitted));
+        optics.cluster_method = ClusterMethod::Dbscan;
+        assert!(matches!(
+            optics.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        optics.cluster_method = ClusterMethod::Xi;
+        optics.xi = 1.0;
+        assert!(matches!(
+            optics.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        optics.xi = 0.05;
+        optics.min_samples = 65;
+        assert!(matches!(
+            optics.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        optics.min_samples = 5;
+        assert_eq!(optics.fit(vec![]), Err(Error::EmptyDataset));
+        optics.max_eps = 5.0;
+        optics.fit(create_test_points()).unwrap();
+        assert!(matches!(
+            optics.extract_dbscan(6.0),
+            Err(Error::InvalidParameter { .. })
+        ));
+        assert!(matches!(
+            optics.extract_xi(0.0, 5),
+            Err(Error::InvalidParameter { .. })
+        ));
+        optics.distance_metric = DistanceMetric::Minkowski(0.0);
+        assert!(matches!(
+            optics.fit(create_test_points()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        assert_eq!("dbscan".parse(), Ok(ClusterMethod::Dbscan));
+    }
+}
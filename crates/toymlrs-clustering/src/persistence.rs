@@ -0,0 +1,239 @@
+//! Saving and loading fitted models, as JSON or in a compact binary format.
+//!
+//! Both formats wrap the model with a format version, checked when loading so a model saved by
+//! an incompatible version of the crate is rejected instead of misread.
+
+use crate::distance::Distance;
+use crate::kmeans::Kmeans;
+use crate::{Error, Float};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Version of the saved model format, bumped whenever a saved model changes shape.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Saved<'a, M> {
+    format_version: u32,
+    model: &'a M,
+}
+
+/// Only the version, read first so an unknown format is reported as such.
+#[derive(Deserialize)]
+struct Version {
+    format_version: u32,
+}
+
+#[derive(Deserialize)]
+struct Loaded<M> {
+    model: M,
+}
+
+fn check_version(version: Version) -> Result<(), Error> {
+    if version.format_version != FORMAT_VERSION {
+        return Err(Error::UnsupportedFormatVersion {
+            expected: FORMAT_VERSION,
+            found: version.format_version,
+        });
+    }
+    Ok(())
+}
+
+fn serialization_error(e: impl std::fmt::Display) -> Error {
+    Error::Serialization(e.to_string())
+}
+
+/// Reject a loaded model the fitting could not have produced, e.g. one edited by hand.
+fn check_model<F: Float, D: Distance<F>>(kmeans: Kmeans<F, D>) -> Result<Kmeans<F, D>, Error> {
+    kmeans.distance_metric.check()?;
+    let centroid_map = &kmeans.get_centroids().centroid_map;
+    // An unfitted model has no centroid
+    if centroid_map.is_empty() {
+        return Ok(kmeans);
+    }
+    if centroid_map.len() != kmeans.k || (0..kmeans.k).any(|i| !centroid_map.contains_key(&i)) {
+        let mut keys: Vec<_> = centroid_map.keys().collect();
+        keys.sort();
+        return Err(Error::Serialization(format!(
+            "expected the centroids 0..{}, found {keys:?}",
+            kmeans.k
+        )));
+    }
+    let dims: Vec<usize> = (0..kmeans.k).map(|i| centroid_map[&i].dim()).collect();
+    if dims.iter().any(|&dim| dim != dims[0]) {
+        return Err(Error::Serialization(format!(
+            "expected centroids of the same dimension, found {dims:?}"
+        )));
+    }
+    Ok(kmeans)
+}
+
+impl<F, D> Kmeans<F, D>
+where
+    F: Float + Serialize + DeserializeOwned,
+    D: Distance<F> + Serialize + DeserializeOwned,
+{
+    /// Save the model, hyperparameters and fitted state, as JSON.
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string(&Saved {
+            format_version: FORMAT_VERSION,
+            model: self,
+        })
+        .map_err(serialization_error)
+    }
+
+    /// Load a model saved with [`Kmeans::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        check_version(serde_json::from_str(json).map_err(serialization_error)?)?;
+        let loaded: Loaded<Self> = serde_json::from_str(json).map_err(serialization_error)?;
+        check_model(loaded.model)
+    }
+
+    /// Save the model, hyperparameters and fitted state, in a compact binary format.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        bincode::serialize(&Saved {
+            format_version: FORMAT_VERSION,
+            model: self,
+        })
+        .map_err(serialization_error)
+    }
+
+    /// Load a model saved with [`Kmeans::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        // The version comes first, the rest of the bytes are ignored when reading it
+        check_version(bincode::deserialize(bytes).map_err(serialization_error)?)?;
+        // Structs are written as tuples of their fields
+        let loaded: (u32, Self) = bincode::deserialize(bytes).map_err(serialization_error)?;
+        check_model(loaded.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kmeans::{CentroidsInitMethod, DistanceMetric, KmeansAlgorithm};
+
+    fn create_fitted_kmeans() -> Kmeans {
+        let mut kmeans = Kmeans::new(
+            2,
+            100,
+            CentroidsInitMethod::KmeansPlusPlus,
+            DistanceMetric::Minkowski(3.0),
+            Some(42),
+        );
+        kmeans.algorithm = KmeansAlgorithm::Hamerly;
+        kmeans
+            .fit(vec![
+                vec![1.0, 0.0],
+                vec![1.0, 1.0],
+                vec![1.0, 2.0],
+                vec![10.0, 0.0],
+                vec![10.0, 1.0],
+                vec![10.0, 2.0],
+            ])
+            .unwrap();
+        kmeans
+    }
+
+    fn assert_same_model(loaded: &Kmeans, kmeans: &Kmeans) {
+        assert_eq!(loaded.k, kmeans.k);
+        assert_eq!(loaded.algorithm, kmeans.algorithm);
+        assert_eq!(loaded.distance_metric, kmeans.distance_metric);
+        assert_eq!(loaded.random_seed, kmeans.random_seed);
+        assert_eq!(loaded.get_labels().0, kmeans.get_labels().0);
+        assert_eq!(loaded.get_clusters(), kmeans.get_clusters());
+        assert_eq!(loaded.get_inertia(), kmeans.get_inertia());
+        assert_eq!(loaded.get_n_iter(), kmeans.get_n_iter());
+        let points = vec![vec![0.0, 0.0], vec![12.0, 3.0]];
+        assert_eq!(
+            loaded.predict(points.clone()).unwrap().0,
+            kmeans.predict(points).unwrap().0
+        );
+    }
+
+    #[test]
+    fn test_kmeans_json_round_trip() {
+        let kmeans = create_fitted_kmeans();
+        let json = kmeans.to_json().unwrap();
+        assert!(json.starts_with(r#"{"format_version":1,"#));
+        assert_same_model(&Kmeans::from_json(&json).unwrap(), &kmeans);
+    }
+
+    #[test]
+    fn test_kmeans_bytes_round_trip() {
+        let kmeans = create_fitted_kmeans();
+        let bytes = kmeans.to_bytes().unwrap();
+        assert_eq!(bytes[..4], FORMAT_VERSION.to_le_bytes());
+        assert_same_model(&Kmeans::from_bytes(&bytes).unwrap(), &kmeans);
+
+        let kmeans_f32: Kmeans<f32> = Kmeans::default();
+        let loaded = Kmeans::<f32>::from_bytes(&kmeans_f32.to_bytes().unwrap()).unwrap();
+        assert_eq!(loaded.tol, kmeans_f32.tol);
+    }
+
+    #[test]
+    fn test_kmeans_load_errors() {
+        let kmeans = create_fitted_kmeans();
+        let json =
+            kmeans
+                .to_json()
+                .unwrap()
+                .replacen(r#""format_version":1"#, r#""format_version":2"#, 1);
+        assert_eq!(
+            Kmeans::<f64>::from_json(&json).unwrap_err(),
+            Error::UnsupportedFormatVersion {
+                expected: 1,
+                found: 2
+            }
+        );
+        let mut bytes = kmeans.to_bytes().unwrap();
+        bytes[0] = 0;
+        assert!(matches!(
+            Kmeans::<f64>::from_bytes(&bytes),
+            Err(Error::UnsupportedFormatVersion { found: 0, .. })
+        ));
+        assert!(matches!(
+            Kmeans::<f64>::from_bytes(&kmeans.to_bytes().unwrap()[..10]),
+            Err(Error::Serialization(_))
+        ));
+        assert!(matches!(
+            Kmeans::<f64>::from_json("{"),
+            Err(Error::Serialization(_))
+        ));
+
+        let mut invalid_metric = create_fitted_kmeans();
+        invalid_metric.distance_metric = DistanceMetric::Minkowski(-1.0);
+        assert!(matches!(
+            Kmeans::<f64>::from_json(&invalid_metric.to_json().unwrap()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        assert!(matches!(
+            Kmeans::<f64>::from_bytes(&invalid_metric.to_bytes().unwrap()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+
+        let mut missing_centroid = create_fitted_kmeans();
+        missing_centroid.k = 3;
+        let error = Error::Serialization("expected the centroids 0..3, found [0, 1]".to_string());
+        assert_eq!(
+            Kmeans::<f64>::from_json(&missing_centroid.to_json().unwrap()).unwrap_err(),
+            error
+        );
+        assert_eq!(
+            Kmeans::<f64>::from_bytes(&missing_centroid.to_bytes().unwrap()).unwrap_err(),
+            error
+        );
+
+        let mut json: serde_json::Value = serde_json::from_str(&kmeans.to_json().unwrap()).unwrap();
+        json["model"]["centroids"]["centroid_map"]["1"]["values"]
+            .as_array_mut()
+            .unwrap()
+            .push(0.0.into());
+        assert_eq!(
+            Kmeans::<f64>::from_json(&json.to_string()).unwrap_err(),
+            Error::Serialization(
+                "expected centroids of the same dimension, found [2, 3]".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,660 @@
+use crate::dataset::{IntoPoints, Points};
+use crate::distance::{Distance, DistanceMetric};
+use crate::kmeans::{CentroidsInitMethod, Kmeans};
+use crate::{Error, Float};
+use parse_display::{Display, FromStr};
+use rand::prelude::{Rng, SeedableRng};
+use rand::random;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+/// How similar two points are in the graph whose cuts separate the clusters.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Display, FromStr)]
+pub enum Affinity {
+    /// `exp(-gamma * d²)` between every pair of points
+    #[default]
+    #[display("rbf")]
+    Rbf,
+    /// 1 between a point and each of its `n_neighbors` nearest points, itself included, halved
+    /// when the relation is not mutual
+    #[display("nearest-neighbors")]
+    NearestNeighbors,
+}
+
+/// Spectral clustering, running k-means on an embedding of the points given by the leading
+/// eigenvectors of the normalized affinity matrix.
+///
+/// The embedding follows the connectivity of the affinity graph rather than the distances to a
+/// center, so clusters of any shape are found as long as they are loosely connected to each
+/// other, e.g. concentric rings. The rows of the embedding are normalized to unit length before
+/// k-means, as in Ng, Jordan and Weiss.
+#[derive(Debug)]
+pub struct SpectralClustering<F: Float = f64, D: Distance<F> = DistanceMetric> {
+    pub n_clusters: usize,
+    pub affinity: Affinity,
+    /// Scale of the RBF affinity, the larger the more local
+    pub gamma: F,
+    /// Number of neighbours of each point, itself included, for the nearest-neighbours affinity
+    pub n_neighbors: usize,
+    /// Number of k-means runs on the embedding, the run with the lowest inertia is kept
+    pub n_init: usize,
+    /// Largest number of iterations of the eigenvector search
+    pub max_iter: usize,
+    pub random_seed: Option<u64>,
+    pub distance_metric: D,
+    labels: Vec<usize>,
+    embedding: Vec<Vec<F>>,
+    eigenvalues: Vec<F>,
+}
+
+impl<F: Float> Default for SpectralClustering<F> {
+    fn default() -> Self {
+        SpectralClustering {
+            n_clusters: 2,
+            affinity: Affinity::default(),
+            gamma: F::one(),
+            n_neighbors: 10,
+            n_init: 10,
+            max_iter: 1000,
+            random_seed: None,
+            distance_metric: DistanceMetric::default(),
+            labels: Vec::new(),
+            embedding: Vec::new(),
+            eigenvalues: Vec::new(),
+        }
+    }
+}
+
+impl<F: Float> SpectralClustering<F> {
+    pub fn new(
+        n_clusters: usize,
+        affinity: Affinity,
+        distance_metric: DistanceMetric,
+        random_seed: Option<u64>,
+    ) -> Self {
+        SpectralClustering {
+            n_clusters,
+            affinity,
+            distance_metric,
+            random_seed,
+            ..SpectralClustering::default()
+        }
+    }
+}
+
+impl<F: Float, D: Distance<F>> SpectralClustering<F, D> {
+    /// Use a custom distance to build the affinity matrix.
+    pub fn with_distance_metric<E: Distance<F>>(
+        self,
+        distance_metric: E,
+    ) -> SpectralClustering<F, E> {
+        SpectralClustering {
+            n_clusters: self.n_clusters,
+            affinity: self.affinity,
+            gamma: self.gamma,
+            n_neighbors: self.n_neighbors,
+            n_init: self.n_init,
+            max_iter: self.max_iter,
+            random_seed: self.random_seed,
+            distance_metric,
+            labels: self.labels,
+            embedding: self.embedding,
+            eigenvalues: self.eigenvalues,
+        }
+    }
+
+    pub fn fit<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        self.distance_metric.check()?;
+        match self.affinity {
+            Affinity::Rbf if !(self.gamma.is_finite() && self.gamma > F::zero()) => {
+                return Err(Error::InvalidParameter {
+                    name: "gamma".to_string(),
+                    reason: "must be positive and finite".to_string(),
+                });
+            }
+            Affinity::NearestNeighbors if self.n_neighbors == 0 => {
+                return Err(Error::InvalidParameter {
+                    name: "n_neighbors".to_string(),
+                    reason: "must be at least 1".to_string(),
+                });
+            }
+            _ => {}
+        }
+        let points = points.into_points()?;
+        points.check()?;
+        self.check_n_clusters(points.len())?;
+        if self.affinity == Affinity::NearestNeighbors && self.n_neighbors > points.len() {
+            return Err(Error::InvalidParameter {
+                name: "n_neighbors".to_string(),
+                reason: format!(
+                    "must be at most the number of points {}, got {}",
+                    points.len(),
+                    self.n_neighbors
+                ),
+            });
+        }
+        let affinity = self.affinity_matrix(&points)?;
+        self.fit_affinity(&affinity)
+    }
+
+    /// Fit from the square matrix of the affinities between the points, e.g. similarities from
+    /// another model.
+    ///
+    /// The affinities are non-negative, the larger the more similar, and the matrix is
+    /// symmetrized as `(A + Aᵀ) / 2`.
+    pub fn fit_precomputed<'a>(&mut self, affinity: impl IntoPoints<'a, F>) -> Result<(), Error> {
+        let affinity = affinity.into_points()?;
+        let found = affinity.check()?;
+        let n = affinity.len();
+        if found != n {
+            return Err(Error::DimensionMismatch { expected: n, found });
+        }
+        if affinity.as_slice().iter().any(|&a| a < F::zero()) {
+            return Err(Error::InvalidParameter {
+                name: "affinity".to_string(),
+                reason: "must be non-negative".to_string(),
+            });
+        }
+        self.check_n_clusters(n)?;
+        let half = F::from_f64(0.5);
+        let symmetric = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .map(|(i, j)| half * (affinity.row(i)[j] + affinity.row(j)[i]))
+            .collect();
+        self.fit_affinity(&Points::new(symmetric, n)?)
+    }
+
+    fn check_n_clusters(&self, n_samples: usize) -> Result<(), Error> {
+        if self.n_clusters == 0 || self.n_clusters > n_samples {
+            return Err(Error::InvalidK {
+                k: self.n_clusters,
+                n_samples,
+            });
+        }
+        Ok(())
+    }
+
+    fn affinity_matrix(&self, points: &Points<F>) -> Result<Points<'static, F>, Error> {
+        let n = points.len();
+        #[cfg(feature = "parallel")]
+        let rows = points.par_rows();
+        #[cfg(not(feature = "parallel"))]
+        let rows = points.rows();
+        let distances: Vec<Vec<F>> = rows
+            .map(|point| {
+                points
+                    .rows()
+                    .map(|other| self.distance_metric.distance(point, other))
+                    .collect()
+            })
+            .collect();
+        let values = match self.affinity {
+            Affinity::Rbf => distances
+                .concat()
+                .into_iter()
+                .map(|d| (-self.gamma * d * d).exp())
+                .collect(),
+            Affinity::NearestNeighbors => {
+                let mut connectivity = vec![F::zero(); n * n];
+                for (i, row) in distances.iter().enumerate() {
+                    let mut order: Vec<usize> = (0..n).collect();
+                    // The point itself first whatever its duplicates
+                    order.sort_by(|&a, &b| {
+                        (a != i)
+                            .cmp(&(b != i))
+                            .then(row[a].partial_cmp(&row[b]).unwrap_or(Ordering::Equal))
+                    });
+                    let half = F::from_f64(0.5);
+                    for &j in &order[..self.n_neighbors] {
+                        connectivity[i * n + j] += half;
+                        connectivity[j * n + i] += half;
+                    }
+                }
+                connectivity
+            }
+        };
+        Points::new(values, n)
+    }
+
+    fn fit_affinity(&mut self, affinity: &Points<F>) -> Result<(), Error> {
+        let n = affinity.len();
+        let degrees: Vec<F> = affinity
+            .rows()
+            .map(|row| row.iter().copied().sum())
+            .collect();
+        if degrees.iter().any(|&degree| degree <= F::zero()) {
+            return Err(Error::InvalidParameter {
+                name: "affinity".to_string(),
+                reason: "every point needs a positive affinity to some point".to_string(),
+            });
+        }
+        // D^-1/2 A D^-1/2 + I shares its eigenvectors with the normalized Laplacian
+        // I - D^-1/2 A D^-1/2, its eigenvalues being in [0, 2] with the leading ones matching
+        // the smallest of the Laplacian
+        let scales: Vec<F> = degrees.iter().map(|degree| degree.sqrt().recip()).collect();
+        let shifted = affinity
+            .rows()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                let scales = &scales;
+                row.iter().enumerate().map(move |(j, &a)| {
+                    let shift = if i == j { F::one() } else { F::zero() };
+                    a * scales[i] * scales[j] + shift
+                })
+            })
+            .collect();
+        let mut rng =
+            rand::rngs::StdRng::seed_from_u64(self.random_seed.unwrap_or(random::<u64>()));
+        let (eigenvalues, eigenvectors) = leading_eigenvectors(
+            &Points::new(shifted, n)?,
+            self.n_clusters,
+            self.max_iter,
+            &mut rng,
+        );
+        let two = F::from_f64(2.0);
+        self.eigenvalues = eigenvalues
+            .into_iter()
+            .map(|eigenvalue| (two - eigenvalue).max(F::zero()))
+            .collect();
+        self.embedding = (0..n)
+            .map(|i| {
+                let row: Vec<F> = eigenvectors.iter().map(|vector| vector[i]).collect();
+                let norm = row.iter().map(|&x| x * x).sum::<F>().sqrt();
+                if norm > F::zero() {
+                    row.into_iter().map(|x| x / norm).collect()
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let mut kmeans = Kmeans::new(
+            self.n_clusters,
+            300,
+            CentroidsInitMethod::KmeansPlusPlus,
+            DistanceMetric::Euclidean,
+            Some(rng.gen::<u64>()),
+        );
+        kmeans.n_init = self.n_init;
+        kmeans.fit(Points::new(self.embedding.concat(), self.n_clusters)?)?;
+        self.labels = kmeans.get_labels().0.clone();
+        Ok(())
+    }
+
+    pub fn fit_predict<'a>(&mut self, points: impl IntoPoints<'a, F>) -> Result<&[usize], Error> {
+        self.fit(points)?;
+        Ok(&self.labels)
+    }
+
+    pub fn get_labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// The points as clustered by k-means, one row of `n_clusters` coordinates of unit length
+    /// per point.
+    pub fn get_embedding(&self) -> &[Vec<F>] {
+        &self.embedding
+    }
+
+    /// The `n_clusters` smallest eigenvalues of the normalized Laplacian, in increasing order.
+    ///
+    /// As many eigenvalues close to 0 as there are loosely connected groups of points, a large
+    /// gap after the last one hinting at a good number of clusters.
+    pub fn get_eigenvalues(&self) -> &[F] {
+        &self.eigenvalues
+    }
+}
+
+fn dot<F: Float>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(&x, &y)| x * y).sum()
+}
+
+/// The product of a square matrix and each of the vectors.
+fn multiply<F: Float>(matrix: &Points<F>, vectors: &[Vec<F>]) -> Vec<Vec<F>> {
+    #[cfg(feature = "parallel")]
+    let rows = matrix.par_rows();
+    #[cfg(not(feature = "parallel"))]
+    let rows = matrix.rows();
+    let products: Vec<Vec<F>> = rows
+        .map(|row| vectors.iter().map(|vector| dot(row, vector)).collect())
+        .collect();
+    (0..vectors.len())
+        .map(|j| products.iter().map(|product| product[j]).collect())
+        .collect()
+}
+
+/// Make the vectors orthonormal with the modified Gram-Schmidt process, a vector depending on
+/// the previous ones becoming zero.
+fn orthonormalize<F: Float>(vectors: &mut [Vec<F>]) {
+    for j in 0..vectors.len() {
+        let (previous, rest) = vectors.split_at_mut(j);
+        let vector = &mut rest[0];
+        for other in previous.iter() {
+            let projection = dot(vector, other);
+            vector
+                .iter_mut()
+                .zip(other)
+                .for_each(|(x, &y)| *x = *x - projection * y);
+        }
+        let norm = dot(vector, vector).sqrt();
+        if norm > F::epsilon() {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        } else {
+            vector.iter_mut().for_each(|x| *x = F::zero());
+        }
+    }
+}
+
+/// The `k` largest eigenvalues, in decreasing order, and the eigenvectors of a symmetric
+/// positive semi-definite matrix.
+///
+/// A few more random vectors than needed are repeatedly multiplied by the matrix, rotated into
+/// the eigenvectors of the matrix restricted to the subspace they span and orthonormalized,
+/// until the `k` leading ones are eigenvectors up to a small residual. The extra vectors make
+/// the convergence fast even when the `k`-th eigenvalue is close to the next one.
+fn leading_eigenvectors<F: Float>(
+    matrix: &Points<F>,
+    k: usize,
+    max_iter: usize,
+    rng: &mut impl Rng,
+) -> (Vec<F>, Vec<Vec<F>>) {
+    let n = matrix.len();
+    let size = (2 * k).max(k + 10).min(n);
+    let mut basis: Vec<Vec<F>> = (0..size)
+        .map(|_| {
+            (0..n)
+                .map(|_| F::from_f64(rng.gen_range(-1.0..1.0)))
+                .collect()
+        })
+        .collect();
+    orthonormalize(&mut basis);
+    let tol = F::epsilon().sqrt();
+    let mut iter = 0;
+    loop {
+        iter += 1;
+        let products = multiply(matrix, &basis);
+        // Rayleigh-Ritz step, the eigenvectors of the matrix restricted to the subspace
+        let projected: Vec<Vec<F>> = basis
+            .iter()
+            .map(|a| products.iter().map(|product| dot(a, product)).collect())
+            .collect();
+        let (eigenvalues, rotation) = symmetric_eigen(projected);
+        let mut order: Vec<usize> = (0..size).collect();
+        order.sort_by(|&a, &b| {
+            eigenvalues[b]
+                .partial_cmp(&eigenvalues[a])
+                .unwrap_or(Ordering::Equal)
+        });
+        let rotated_products = rotate(&products, &rotation, &order);
+        let scale = eigenvalues[order[0]].max(F::epsilon());
+        let converged = iter >= max_iter
+            || order[..k]
+                .iter()
+                .zip(&rotated_products)
+                .all(|(&j, product)| {
+                    let vector = rotate(&basis, &rotation, &[j]).remove(0);
+                    let residual = product
+                        .iter()
+                        .zip(&vector)
+                        .map(|(&p, &v)| {
+                            let r = p - eigenvalues[j] * v;
+                            r * r
+                        })
+                        .sum::<F>()
+                        .sqrt();
+                    residual <= tol * scale
+                });
+        if converged {
+            let eigenvectors = rotate(&basis, &rotation, &order[..k]);
+            return (
+                order[..k].iter().map(|&j| eigenvalues[j]).collect(),
+                eigenvectors,
+            );
+        }
+        basis = rotated_products;
+        orthonormalize(&mut basis);
+    }
+}
+
+/// The linear combinations of the vectors given by the selected columns of `rotation`.
+fn rotate<F: Float>(vectors: &[Vec<F>], rotation: &[Vec<F>], columns: &[usize]) -> Vec<Vec<F>> {
+    let n = vectors.first().map_or(0, Vec::len);
+    columns
+        .iter()
+        .map(|&j| {
+            let mut combination = vec![F::zero(); n];
+            for (vector, weights) in vectors.iter().zip(rotation) {
+                combination
+                    .iter_mut()
+                    .zip(vector)
+                    .for_each(|(c, &x)| *c += weights[j] * x);
+            }
+            combination
+        })
+        .collect()
+}
+
+/// The eigenvalues and the eigenvectors, as the columns of the returned matrix, of a small
+/// symmetric matrix, with the cyclic Jacobi method.
+fn symmetric_eigen<F: Float>(matrix: Vec<Vec<F>>) -> (Vec<F>, Vec<Vec<F>>) {
+    let k = matrix.len();
+    // Symmetrize the rounding errors away
+    let half = F::from_f64(0.5);
+    let mut matrix: Vec<Vec<F>> = (0..k)
+        .map(|p| {
+            (0..k)
+                .map(|q| half * (matrix[p][q] + matrix[q][p]))
+                .collect()
+        })
+        .collect();
+    let mut vectors: Vec<Vec<F>> = (0..k)
+        .map(|i| {
+            (0..k)
+                .map(|j| if i == j { F::one() } else { F::zero() })
+                .collect()
+        })
+        .collect();
+    let total: F = matrix.iter().flatten().map(|&x| x * x).sum();
+    for _ in 0..100 {
+        let off_diagonal: F = (0..k)
+            .flat_map(|p| (0..k).filter(move |&q| q != p).map(move |q| (p, q)))
+            .map(|(p, q)| matrix[p][q] * matrix[p][q])
+            .sum();
+        if off_diagonal <= F::epsilon() * F::epsilon() * total {
+            break;
+        }
+        for p in 0..k {
+            for q in p + 1..k {
+                if matrix[p][q] == F::zero() {
+                    continue;
+                }
+                // The rotation zeroing the (p, q) entry
+                let theta = (matrix[q][q] - matrix[p][p]) / (F::from_f64(2.0) * matrix[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + F::one()).sqrt());
+                let c = (t * t + F::one()).sqrt().recip();
+                let s = t * c;
+                for row in matrix.iter_mut().chain(vectors.iter_mut()) {
+                    let (x, y) = (row[p], row[q]);
+                    row[p] = c * x - s * y;
+                    row[q] = s * x + c * y;
+                }
+                let (upper, lower) = matrix.split_at_mut(q);
+                for (x, y) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    (*x, *y) = (c * *x - s * *y, s * *x + c * *y);
+                }
+            }
+        }
+    }
+    ((0..k).map(|i| matrix[i][i]).collect(), vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    /// An inner ring of radius 1 and an outer ring of radius 4 around the same center.
+    fn create_test_points() -> Vec<Vec<f64>> {
+        [(1.0, 20), (4.0, 40)]
+            .iter()
+            .flat_map(|&(radius, n)| {
+                (0..n).map(move |i| {
+                    let angle = 2.0 * PI * i as f64 / n as f64;
+                    vec![radius * angle.cos(), radius * angle.sin()]
+                })
+            })
+            .collect()
+    }
+
+    fn assert_rings(labels: &[usize]) {
+        assert_ne!(labels[0], labels[20]);
+        assert!(
+            (0..60).all(|i| labels[i] == if i < 20 { labels[0] } else { labels[20] }),
+            "{labels:?}"
+        );
+    }
+
+    #[test]
+    fn test_spectral_clustering_separates_rings() {
+        for affinity in [Affinity::Rbf, Affinity::NearestNeighbors] {
+            let mut spectral =
+                SpectralClustering::new(2, affinity, DistanceMetric::Euclidean, Some(42));
+            spectral.n_neighbors = 5;
+            assert_rings(spectral.fit_predict(create_test_points()).unwrap());
+            assert_eq!(spectral.get_embedding().len(), 60);
+            assert!(spectral
+                .get_embedding()
+                .iter()
+                .all(|row| (row[0].hypot(row[1]) - 1.0).abs() < 1e-9));
+            let eigenvalues = spectral.get_eigenvalues();
+            assert!(
+                eigenvalues[0] < 1e-6 && eigenvalues[1] < 1e-3,
+                "{affinity} {eigenvalues:?}"
+            );
+        }
+
+        // K-means splits the plane in two halves instead
+        let mut kmeans = Kmeans::new(
+            2,
+            100,
+            CentroidsInitMethod::KmeansPlusPlus,
+            DistanceMetric::Euclidean,
+            Some(42),
+        );
+        let labels = &kmeans.fit_predict(create_test_points()).unwrap().0;
+        assert!((20..60).any(|i| labels[i] == labels[0]));
+    }
+
+    #[test]
+    fn test_spectral_clustering_precomputed() {
+        // Two cliques joined by a weak edge
+        let n = 6;
+        let affinity: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| match (i < 3, j < 3) {
+                        (a, b) if a == b => 1.0,
+                        _ if (i, j) == (2, 3) => 0.1,
+                        _ => 0.0,
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut spectral =
+            SpectralClustering::new(2, Affinity::Rbf, DistanceMetric::Euclidean, Some(7));
+        spectral.fit_precomputed(affinity).unwrap();
+        let labels = spectral.get_labels();
+        assert!((0..n).all(|i| (labels[i] == labels[0]) == (i < 3)));
+        let eigenvalues = spectral.get_eigenvalues();
+        assert!(eigenvalues[0].abs() < 1e-6);
+        assert!(eigenvalues[1] > 0.0 && eigenvalues[1] < 0.1);
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let matrix = vec![
+            vec![2.0, 1.0, 0.0],
+            vec![1.0, 2.0, 1.0],
+            vec![0.0, 1.0, 2.0],
+        ];
+        let (eigenvalues, vectors) = symmetric_eigen(matrix.clone());
+        let mut sorted = eigenvalues.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let expected = [2.0 - 2.0_f64.sqrt(), 2.0, 2.0 + 2.0_f64.sqrt()];
+        assert!(sorted
+            .iter()
+            .zip(expected)
+            .all(|(x, y)| (x - y).abs() < 1e-12));
+        for (j, eigenvalue) in eigenvalues.iter().enumerate() {
+            for i in 0..3 {
+                let product: f64 = (0..3).map(|a| matrix[i][a] * vectors[a][j]).sum();
+                assert!((product - eigenvalue * vectors[i][j]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_spectral_clustering_f32() {
+        let values: Vec<f32> = create_test_points()
+            .concat()
+            .into_iter()
+            .map(|x| x as f32)
+            .collect();
+        let mut spectral = SpectralClustering::new(
+            2,
+            Affinity::NearestNeighbors,
+            DistanceMetric::Euclidean,
+            Some(3),
+        );
+        spectral.n_neighbors = 5;
+        spectral.fit(Points::view(&values, 2).unwrap()).unwrap();
+        assert_rings(spectral.get_labels());
+    }
+
+    #[test]
+    fn test_spectral_clustering_errors() {
+        let mut spectral = SpectralClustering::<f64>::default();
+        assert_eq!(
+            spectral.fit_precomputed(vec![vec![1.0, 0.5, 0.0], vec![0.5, 1.0, 0.0]]),
+            Err(Error::DimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert!(matches!(
+            spectral.fit_precomputed(vec![vec![1.0, -1.0], vec![-1.0, 1.0]]),
+            Err(Error::InvalidParameter { .. })
+        ));
+        assert!(matches!(
+            spectral.fit_precomputed(vec![vec![1.0, 0.0], vec![0.0, 0.0]]),
+            Err(Error::InvalidParameter { .. })
+        ));
+        spectral.gamma = 0.0;
+        assert!(matches!(
+            spectral.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        spectral.affinity = Affinity::NearestNeighbors;
+        spectral.n_neighbors = 61;
+        assert!(matches!(
+            spectral.fit(create_test_points()),
+            Err(Error::InvalidParameter { .. })
+        ));
+        spectral.n_clusters = 61;
+        assert_eq!(
+            spectral.fit(create_test_points()),
+            Err(Error::InvalidK {
+                k: 61,
+                n_samples: 60
+            })
+        );
+        assert_eq!(spectral.fit(vec![]), Err(Error::EmptyDataset));
+        spectral.distance_metric = DistanceMetric::Minkowski(0.0);
+        assert!(matches!(
+            spectral.fit(create_test_points()),
+            Err(Error::InvalidParameter { name, .. }) if name == "p"
+        ));
+        assert_eq!("nearest-neighbors".parse(), Ok(Affinity::NearestNeighbors));
+    }
+}